@@ -1,5 +1,7 @@
+#[cfg(feature = "gui")]
 use std::{env, fs, path::PathBuf};
 
+#[cfg(feature = "gui")]
 fn main() {
     // Get the OUT_DIR environment variable
     let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
@@ -19,3 +21,7 @@ fn main() {
         target_dir.to_str().unwrap(),
     );
 }
+
+// The resources are only needed by the GTK app
+#[cfg(not(feature = "gui"))]
+fn main() {}
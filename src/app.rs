@@ -3,20 +3,39 @@
 pub mod logic;
 pub use logic::todo_list;
 
+#[cfg(feature = "gui")]
+use std::{path::Path, rc::Rc};
+
+#[cfg(feature = "gui")]
 use gtk::prelude::*;
+#[cfg(feature = "gui")]
 use gtk::{gio, glib, Application};
 
-use crate::AppWindow;
+#[cfg(feature = "gui")]
+use crate::{AppWindow, CsvStorage, Storage};
 
 /// The application id.
+#[cfg(feature = "gui")]
 const APP_ID: &str = "io.github.dracoy-code.RusticTasks";
 
 /// The main app structure.
+#[cfg(feature = "gui")]
 pub struct App;
 
+#[cfg(feature = "gui")]
 impl App {
-    /// Builds and runs the app UI.
+    /// Builds and runs the app UI with the default CSV storage.
     pub fn build() -> glib::ExitCode {
+        let storage = CsvStorage::new(
+            Path::new("./data/author.csv"),
+            Path::new("./data/tasks.csv"),
+        );
+
+        Self::build_with_storage(Rc::new(storage))
+    }
+
+    /// Builds and runs the app UI with the given storage backend.
+    pub fn build_with_storage(storage: Rc<dyn Storage>) -> glib::ExitCode {
         // Register the resources to build the app
         gio::resources_register_include!("resources.gresource")
             .expect("Failed to register resources.");
@@ -26,9 +45,13 @@ impl App {
             .application_id(APP_ID)
             .build();
         
+        // Bind the shortcuts of the window actions
+        application.set_accels_for_action("win.undo", &["<Ctrl>z"]);
+        application.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
+
         // Connect the app to the window
-        application.connect_activate(|app| {
-            let window = AppWindow::new(app);
+        application.connect_activate(move |app| {
+            let window = AppWindow::new(app, storage.clone());
             window.present();
         });
 
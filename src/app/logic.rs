@@ -1,11 +1,15 @@
 /// The module that handles the
 /// column in the to-do list UI.
+#[cfg(feature = "gui")]
 pub mod task_box;
+#[cfg(feature = "gui")]
 pub use task_box::TaskBox;
 
 /// The module that handles the
 /// task entries in the to-do list.
+#[cfg(feature = "gui")]
 pub mod task_entry;
+#[cfg(feature = "gui")]
 pub use task_entry::TaskEntry;
 
 /// The module that handles the
@@ -13,14 +17,43 @@ pub use task_entry::TaskEntry;
 pub mod todo_list;
 pub use todo_list::{
     Author,
+    AuthorProfile,
+    Comparison,
+    CsvStorage,
+    DueBucket,
+    Filter,
+    GroupBy,
+    GroupKey,
+    Journal,
+    JournalEntry,
+    JournalEvent,
+    JournalStorage,
+    JsonDocument,
+    JsonStorage,
     LoginState,
+    MemoryStorage,
     Priority,
+    Query,
+    Recurrence,
+    ReportOptions,
+    Session,
+    SortKey,
+    SortOrder,
+    SqliteStorage,
     Status,
+    Storage,
     Task,
+    TaskCommand,
+    TaskEdit,
+    TaskEvent,
+    TaskField,
     ToDoList,
+    UndoStack,
 };
 
 /// The module that handles the
 /// UI logic of the app window.
+#[cfg(feature = "gui")]
 pub mod window;
+#[cfg(feature = "gui")]
 pub use window::AppWindow;
@@ -4,7 +4,7 @@ mod imp;
 use glib::Object;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
-use gtk::{glib, pango};
+use gtk::{glib, pango, TreeListRow};
 use pango::{AttrInt, AttrList};
 
 use crate::TaskEntry;
@@ -30,8 +30,11 @@ impl TaskBox {
         Object::builder().build()
     }
 
-    /// Binds properties of a TaskEntry to this TaskBox.
-    pub fn bind(&self, task_entry: &TaskEntry) {
+    /// Binds properties of a TaskEntry to this TaskBox, with
+    /// the expander following the row in the task tree.
+    pub fn bind(&self, task_entry: &TaskEntry, list_row: &TreeListRow) {
+        self.imp().expander.set_list_row(Some(list_row));
+
         let completed_button = self.imp().completed_button.get();
         let content_label = self.imp().content_label.get();
         let mut bindings = self.imp().bindings.borrow_mut();
@@ -77,10 +80,18 @@ impl TaskBox {
             })
             .build();
         bindings.push(content_label_binding);
+
+        // Point the remove button at the task
+        self.imp()
+            .remove_button
+            .set_action_target_value(Some(&task_entry.task_id().to_variant()));
     }
 
     /// Unbinds all previously established property bindings.
     pub fn unbind(&self) {
+        self.imp().expander.set_list_row(None);
+        self.imp().remove_button.set_action_target_value(None);
+
         for binding in self.imp().bindings.borrow_mut().drain(..) {
             binding.unbind();
         }
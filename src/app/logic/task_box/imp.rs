@@ -2,17 +2,21 @@ use std::cell::RefCell;
 
 use glib::{subclass::InitializingObject, Binding};
 use gtk::subclass::prelude::*;
-use gtk::{glib, CheckButton, CompositeTemplate, Label};
+use gtk::{glib, Button, CheckButton, CompositeTemplate, Label, TreeExpander};
 
 /// The TaskBox widget represents a graphical component
 /// for displaying and interacting with a task.
 #[derive(CompositeTemplate, Default)]
 #[template(resource = "/io/github/dracoy-code/RusticTasks/task_box.ui")]
 pub struct TaskBox {
+    #[template_child]
+    pub expander: TemplateChild<TreeExpander>,
     #[template_child]
     pub completed_button: TemplateChild<CheckButton>,
     #[template_child]
     pub content_label: TemplateChild<Label>,
+    #[template_child]
+    pub remove_button: TemplateChild<Button>,
     pub bindings: RefCell<Vec<Binding>>,
 }
 
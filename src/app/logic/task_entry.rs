@@ -2,7 +2,10 @@
 mod imp;
 
 use glib::Object;
-use gtk::glib;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::{Status, Task};
 
 glib::wrapper! {
     /// Wrapper for the TaskEntry GObject subclass.
@@ -17,12 +20,52 @@ impl TaskEntry {
             .property("content", content)
             .build()
     }
+
+    /// Creates a new instance of TaskEntry that mirrors the task.
+    pub fn from_task(task: &Task) -> Self {
+        let task_entry: TaskEntry = Object::builder()
+            .property("task-id", task.get_id().to_string())
+            .build();
+        task_entry.update(task);
+
+        task_entry
+    }
+
+    /// Returns the entries of the subtasks.
+    pub fn children(&self) -> gio::ListStore {
+        self.imp()
+            .children
+            .get_or_init(gio::ListStore::new::<TaskEntry>)
+            .clone()
+    }
+
+    /// Updates the properties with the details of the task.
+    pub fn update(&self, task: &Task) {
+        let due_date = task
+            .get_due_date()
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+
+        self.set_completed(task.get_status() == &Status::Complete);
+        self.set_content(task.get_name());
+        self.set_description(task.get_description().unwrap_or_default());
+        self.set_priority(task.get_priority().value());
+        self.set_status(task.get_status().to_string());
+        self.set_due_date(due_date);
+        self.set_labels(task.get_labels().join(", "));
+    }
 }
 
 /// The structure that maintains
 /// the task entry metadata.
 #[derive(Default)]
 pub struct TaskData {
+    pub task_id: String,
     pub completed: bool,
     pub content: String,
+    pub description: String,
+    pub priority: u32,
+    pub status: String,
+    pub due_date: String,
+    pub labels: String,
 }
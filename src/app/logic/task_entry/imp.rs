@@ -1,7 +1,7 @@
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 
 use glib::Properties;
-use gtk::glib;
+use gtk::{gio, glib};
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
@@ -11,9 +11,16 @@ use super::TaskData;
 #[derive(Default, Properties)]
 #[properties(wrapper_type = super::TaskEntry)]
 pub struct TaskEntry {
+    #[property(name = "task-id", get, set, type = String, member = task_id)]
     #[property(name = "completed", get, set, type = bool, member = completed)]
     #[property(name = "content", get, set, type = String, member = content)]
+    #[property(name = "description", get, set, type = String, member = description)]
+    #[property(name = "priority", get, set, type = u32, member = priority)]
+    #[property(name = "status", get, set, type = String, member = status)]
+    #[property(name = "due-date", get, set, type = String, member = due_date)]
+    #[property(name = "labels", get, set, type = String, member = labels)]
     pub metadata: RefCell<TaskData>,
+    pub children: OnceCell<gio::ListStore>,
 }
 
 #[glib::object_subclass]
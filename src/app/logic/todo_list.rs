@@ -4,11 +4,70 @@ pub use author::{Author, LoginState};
 
 /// The module that maintains the task details.
 pub mod task;
-pub use task::{Priority, Status, Task};
+pub use task::{Priority, Recurrence, Status, Task, TaskEvent, TaskField};
+pub(crate) use task::TaskBuilder;
 
-use std::{fs, path::Path};
+/// The module that handles the session of the logged-in author.
+pub mod session;
+pub use session::Session;
 
+/// The module that handles the storage backends.
+pub mod storage;
+pub use storage::{
+    CsvStorage,
+    JournalStorage,
+    JsonStorage,
+    MemoryStorage,
+    SqliteStorage,
+    Storage,
+};
+
+/// The module that parses the queries that filter the tasks.
+pub mod query;
+pub use query::{Comparison, Filter, Query};
+
+/// The module that sorts and groups the tasks.
+pub mod view;
+pub use view::{DueBucket, GroupBy, GroupKey, SortKey, SortOrder};
+
+/// The module that converts the tasks to and from iCalendar.
+pub mod ical;
+
+/// The module that converts the tasks to and from todo.txt.
+pub mod todo_txt;
+
+/// The module that renders the to-do list as reports.
+pub mod report;
+pub use report::ReportOptions;
+
+/// The module that exports and imports the versioned JSON documents.
+pub mod document;
+pub use document::{AuthorProfile, JsonDocument};
+
+/// The module that records the changes to the tasks in a journal.
+pub mod journal;
+pub use journal::{Journal, JournalEntry, JournalEvent};
+
+/// The module that undoes and redoes the changes to the to-do list.
+pub mod undo;
+pub use undo::{TaskCommand, TaskEdit, UndoStack};
+
+/// The module that handles the dependencies between the tasks.
+mod dependencies;
+
+/// The module that handles the subtasks in the to-do list.
+mod hierarchy;
+
+/// The module that contains extra utility
+/// functions for the ToDoList structure.
+pub mod utils;
+use utils::*;
+
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, Local};
 use csv::WriterBuilder;
+use uuid::Uuid;
 
 use crate::RusticError;
 
@@ -18,6 +77,7 @@ use crate::RusticError;
 pub struct ToDoList {
     author: Author,
     tasks: Vec<Task>,
+    undo_stack: UndoStack,
 }
 
 impl ToDoList {
@@ -25,7 +85,7 @@ impl ToDoList {
     pub fn build(author: Author) -> Result<Self, RusticError> {
         let tasks: Vec<Task> = Vec::new();
         
-        Ok(ToDoList { author, tasks })
+        Ok(ToDoList { author, tasks, undo_stack: UndoStack::default() })
     }
 
     /// Returns an immutable borrow to the `author` field.
@@ -38,11 +98,219 @@ impl ToDoList {
         &self.tasks
     }
 
-    /// Adds a new task to the to-do list.
+    /// Adds a new task to the to-do list. This cannot be undone,
+    /// unlike executing `TaskCommand::Add`.
     pub fn add(&mut self, task: Task) {
         self.tasks.push(task);
     }
 
+    /// Adds the tasks as tasks of the author, skipping the tasks
+    /// that are already in the list, and returns the number of
+    /// added tasks.
+    ///
+    /// The tasks of other authors are given ids of their own, made from
+    /// the id of the author and the old id, so that they do not clash
+    /// with the tasks they were copied from in a shared storage, while
+    /// importing them again still finds them. The links between the
+    /// imported tasks follow the new ids. The tasks whose old ids are
+    /// in the list are skipped as well, since iCalendar and todo.txt do
+    /// not keep the author, which reads the exports of the author back
+    /// as tasks of another author.
+    pub fn import(&mut self, tasks: Vec<Task>) -> usize {
+        let ids: HashMap<Uuid, Uuid> = tasks
+            .iter()
+            .filter(|task| !task.is_created_by(&self.author))
+            .map(|task| {
+                (*task.get_id(), Uuid::new_v5(self.author.get_id(), task.get_id().as_bytes()))
+            })
+            .collect();
+        let new_id = |id: &Uuid| *ids.get(id).unwrap_or(id);
+
+        let mut count = 0;
+        for task in tasks {
+            let mut builder = TaskBuilder::from(task);
+            let task = builder.as_task();
+            let (id, parent) = (new_id(task.get_id()), task.get_parent().map(new_id));
+            let subtasks = task.get_subtask_ids().iter().map(new_id).collect();
+            let blocked_by = task.get_blockers().iter().map(new_id).collect();
+            if self.get(&id).is_ok() || self.get(task.get_id()).is_ok() {
+                continue;
+            }
+
+            builder
+                .id(id)
+                .parent(parent)
+                .subtasks(subtasks)
+                .blocked_by(blocked_by)
+                .created_by(&self.author.get_id().to_string());
+            self.add(builder.build());
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Adds the tasks read from the file, such as with `read_ical`,
+    /// `read_todo_txt` or `read_json`, like `import` does.
+    pub fn import_from<F>(&mut self, file_path: &Path, read: F) -> Result<usize, RusticError>
+    where
+        F: FnOnce(&str) -> Result<Vec<Task>, RusticError>,
+    {
+        let tasks = read(&fs::read_to_string(file_path)?)?;
+
+        Ok(self.import(tasks))
+    }
+
+    /// Returns an immutable borrow to the task with the given id.
+    pub fn get(&self, id: &Uuid) -> Result<&Task, RusticError> {
+        self.tasks
+            .iter()
+            .find(|task| task.get_id() == id)
+            .ok_or(RusticError::TaskNotFound(*id))
+    }
+
+    /// Returns a mutable borrow to the task with the given id.
+    pub fn get_mut(&mut self, id: &Uuid) -> Result<&mut Task, RusticError> {
+        self.tasks
+            .iter_mut()
+            .find(|task| task.get_id() == id)
+            .ok_or(RusticError::TaskNotFound(*id))
+    }
+
+    /// Returns the changes made to the tasks from `start` up to, but not
+    /// including, `end` along with their tasks, from the oldest change.
+    pub fn get_changes_between(
+        &self,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+    ) -> Vec<(&Task, &TaskEvent)> {
+        let mut changes: Vec<(&Task, &TaskEvent)> = self.tasks
+            .iter()
+            .flat_map(|task| {
+                task.get_changes_between(start, end).into_iter().map(move |event| (task, event))
+            })
+            .collect();
+        changes.sort_by_key(|(_, event)| *event.get_changed_on());
+
+        changes
+    }
+
+    /// Removes the task with the given id along with its subtasks and returns it.
+    /// This cannot be undone, unlike executing `TaskCommand::Remove`.
+    pub fn remove(&mut self, id: &Uuid) -> Result<Task, RusticError> {
+        Ok(self.remove_tree(id)?.remove(0))
+    }
+
+    /// Keeps only the tasks that match the predicate, where the
+    /// other tasks are removed along with their subtasks.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&Task) -> bool,
+    {
+        let ids: Vec<Uuid> = self.tasks
+            .iter()
+            .filter(|task| !predicate(task))
+            .map(|task| *task.get_id())
+            .collect();
+
+        self.remove_trees(&ids);
+    }
+
+    /// Returns the tasks matching the query, such as
+    /// `status:pending label:Work "presentation"`.
+    pub fn query(&self, query: &str) -> Result<Vec<&Task>, RusticError> {
+        let query: Query = query.parse()?;
+
+        Ok(self.tasks.iter().filter(|task| query.matches(task)).collect())
+    }
+
+    /// Marks the task with the given id as completed and adds the next
+    /// occurrence if the task is recurring, returning the id of the
+    /// next occurrence. The parent task is completed as well once all
+    /// of its subtasks are complete. Completing a task that is already
+    /// complete does nothing.
+    pub fn complete(&mut self, id: &Uuid) -> Result<Option<Uuid>, RusticError> {
+        if self.get(id)?.get_status() == &Status::Complete {
+            return Ok(None);
+        }
+        if self.is_blocked(id)? {
+            return Err(RusticError::TaskBlocked(*id));
+        }
+
+        let task = self.get_mut(id)?;
+        task.update_status(Status::Complete);
+
+        let next_task = task.mark_completed();
+        let next_id = next_task.as_ref().map(|next_task| *next_task.get_id());
+        if let Some(next_task) = next_task {
+            // Keep the next occurrence right after the completed subtask
+            let parent_id = next_task.get_parent().copied();
+            if let Some(parent) = parent_id.and_then(|parent_id| self.get_mut(&parent_id).ok()) {
+                let index = parent
+                    .get_subtask_ids()
+                    .iter()
+                    .position(|subtask_id| subtask_id == id)
+                    .map_or(usize::MAX, |index| index + 1);
+                parent.insert_subtask(index, *next_task.get_id());
+            }
+            self.add(next_task);
+        }
+
+        self.roll_up_completion(id)?;
+
+        Ok(next_id)
+    }
+
+    /// Sets the task with the given id back to pending,
+    /// along with its parent if it was complete.
+    pub fn reopen(&mut self, id: &Uuid) -> Result<(), RusticError> {
+        let task = self.get_mut(id)?;
+        task.reopen();
+        self.roll_up_completion(id)
+    }
+
+    /// Marks every unfinished task with the label as completed, skipping
+    /// the blocked ones, and returns the number of completed tasks.
+    pub fn complete_with_label(&mut self, label: &str) -> usize {
+        let ids: Vec<Uuid> = self.tasks
+            .iter()
+            .filter(|task| task.get_status() != &Status::Complete && task.has_label(label))
+            .map(|task| *task.get_id())
+            .collect();
+
+        ids.iter().filter(|id| self.complete(id).is_ok()).count()
+    }
+
+    /// Removes every task with the label along with
+    /// their subtasks and returns them.
+    pub fn remove_with_label(&mut self, label: &str) -> Vec<Task> {
+        let ids: Vec<Uuid> = self.tasks
+            .iter()
+            .filter(|task| task.has_label(label))
+            .map(|task| *task.get_id())
+            .collect();
+
+        self.remove_trees(&ids)
+    }
+
+    /// Reads the tasks created by the author from a CSV file. The tasks
+    /// saved by the name of the author are upgraded like `CsvStorage`
+    /// does, with the author as the only one known.
+    pub fn read_from_csv(author: Author, file_path: &Path) -> Result<Self, RusticError> {
+        let mut todo_list = ToDoList::build(author)?;
+        let mut tasks = read_tasks_from_csv(file_path)?;
+        upgrade_task_owners(&mut tasks, std::slice::from_ref(&todo_list.author));
+
+        // Keep the tasks owned by the author
+        for task in tasks {
+            if task.is_created_by(&todo_list.author) {
+                todo_list.add(task);
+            }
+        }
+
+        Ok(todo_list)
+    }
+
     /// Writes the to-do list into a CSV file.
     pub fn write_to_csv(&self, file_path: &Path) -> Result<(), RusticError> {
         // Create and open the file if it does not exist
@@ -54,6 +322,13 @@ impl ToDoList {
         // Check if the file is empty or not
         let is_empty = file.metadata()?.len() == 0;
 
+        // Rewrite the files that have older columns instead of appending
+        if !is_empty && !has_current_task_headers(file_path)? {
+            let mut tasks = read_tasks_from_csv(file_path)?;
+            tasks.extend(self.tasks.iter().cloned());
+            return rewrite_tasks_to_csv(&tasks, file_path);
+        }
+
         // Reopen the file to append new tasks
         let file = fs::OpenOptions::new()
             .append(true)
@@ -73,4 +348,25 @@ impl ToDoList {
 
         Ok(())
     }
+
+    /// Saves the to-do list into a CSV file by rewriting it.
+    ///
+    /// Rows are keyed by the task id: edited tasks replace their
+    /// old rows, removed tasks are dropped and rows owned by other
+    /// authors are kept as they are.
+    pub fn save_to_csv(&self, file_path: &Path) -> Result<(), RusticError> {
+        save_tasks_to_csv(&self.author, &self.tasks, file_path)
+    }
+
+    /// Loads the tasks of the author from the storage backend.
+    pub fn load(author: Author, storage: &dyn Storage) -> Result<Self, RusticError> {
+        let tasks = storage.load_tasks(&author)?;
+
+        Ok(ToDoList { author, tasks, undo_stack: UndoStack::default() })
+    }
+
+    /// Saves the tasks of the to-do list into the storage backend.
+    pub fn save(&self, storage: &dyn Storage) -> Result<(), RusticError> {
+        storage.save_tasks(&self.author, &self.tasks)
+    }
 }
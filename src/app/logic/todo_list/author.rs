@@ -10,10 +10,11 @@ use csv::{ReaderBuilder, WriterBuilder};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::Storage;
 use crate::RusticError;
 
 /// The attributes associated with the current user.
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct Author {
     id: Uuid,
     name: String,
@@ -47,6 +48,24 @@ impl Author {
         })
     }
 
+    /// Restores the author read back from a storage backend,
+    /// whose password is already hashed.
+    pub(crate) fn from_saved(
+        id: Uuid,
+        name: &str,
+        email: &str,
+        password: &str,
+        created_on: DateTime<Local>,
+    ) -> Self {
+        Author {
+            id,
+            name: name.to_string(),
+            email: email.to_string(),
+            password: password.to_string(),
+            created_on,
+        }
+    }
+
     /// Returns an immutable borrow to the `id` field.
     pub fn get_id(&self) -> &Uuid {
         &self.id
@@ -72,35 +91,45 @@ impl Author {
         &self.created_on
     }
 
-    /// Verifies the login credentials and updates the login state.
+    /// Verifies the login credentials and returns the login state,
+    /// which carries the saved author if the login is successful.
     pub fn login(
         email: &str,
         password: &str,
-        file_path: &Path
+        storage: &dyn Storage,
     ) -> Result<LoginState, RusticError> {
-        // Open the file that contains the login credentials
-        let file = fs::OpenOptions::new()
-            .read(true)
-            .open(file_path)?;
-
-        // Create the reader and add the headers
-        let mut reader = ReaderBuilder::new()
-            .has_headers(true)
-            .from_reader(file);
-        
         // Find the author based on the input email
-        for result in reader.deserialize() {
-            let author: Author = result?;
-            if author.email == email {
-                if verify_password(&password, &author.password)? {
-                    return Ok(LoginState::LoggedIn);
-                } else {
-                    return Ok(LoginState::InvalidCredentials);
-                }
-            }
+        match storage.find_author(email)? {
+            Some(author) if verify_password(password, &author.password)? => {
+                Ok(LoginState::LoggedIn(author))
+            },
+            Some(_) => Ok(LoginState::InvalidCredentials),
+            None => Ok(LoginState::DoesNotExist),
+        }
+    }
+
+    /// Creates a new author and saves it into the storage backend,
+    /// failing with `AuthorExists` if the email is already taken.
+    pub fn register(
+        name: &str,
+        email: &str,
+        password: &str,
+        storage: &dyn Storage,
+    ) -> Result<Self, RusticError> {
+        // Check before hashing the password
+        if storage.find_author(email)?.is_some() {
+            return Err(RusticError::AuthorExists);
         }
 
-        Ok(LoginState::DoesNotExist)
+        let author = Author::build(name, email, password)?;
+        author.save(storage)?;
+
+        Ok(author)
+    }
+
+    /// Saves the author into the storage backend.
+    pub fn save(&self, storage: &dyn Storage) -> Result<(), RusticError> {
+        storage.save_author(self)
     }
 
     /// Writes the author credentials into a CSV file.
@@ -124,13 +153,10 @@ impl Author {
             .has_headers(!is_empty)
             .from_reader(file);
 
-        // Deserialise the new author and get the id
-        let new_author: Author = serde_json::from_str(&serde_json::to_string(self)?)?;
-
         // Check if the author already exists
         for result in reader.deserialize() {
             let existing_author: Author = result?;
-            if existing_author.email == new_author.email {
+            if existing_author.email == self.email {
                 return Err(RusticError::AuthorExists);
             }
         }
@@ -156,7 +182,7 @@ impl Author {
 /// The current login state.
 #[derive(Debug, Default, PartialEq)]
 pub enum LoginState {
-    LoggedIn,
+    LoggedIn(Author),
     DoesNotExist,
     #[default]
     InvalidCredentials,
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use super::{Status, Task, ToDoList};
+use crate::RusticError;
+
+impl ToDoList {
+    /// Marks the task as blocked by another task, rejecting
+    /// the blocker if it already depends on the task.
+    pub fn add_blocker(&mut self, id: &Uuid, blocker_id: &Uuid) -> Result<(), RusticError> {
+        self.get(blocker_id)?;
+        if self.get(id)?.get_blockers().contains(blocker_id) {
+            return Ok(());
+        }
+
+        if id == blocker_id || self.depends_on(blocker_id, id) {
+            return Err(RusticError::DependencyCycle(*id, *blocker_id));
+        }
+
+        let task = self.get_mut(id)?;
+        task.add_blocker(*blocker_id);
+
+        Ok(())
+    }
+
+    /// Removes the blocker from the task, returning
+    /// whether the task was blocked by it.
+    pub fn remove_blocker(&mut self, id: &Uuid, blocker_id: &Uuid) -> Result<bool, RusticError> {
+        let task = self.get_mut(id)?;
+
+        Ok(task.remove_blockers(&[*blocker_id]))
+    }
+
+    /// Checks if the task is blocked by the other task, directly or through
+    /// the blockers of its blockers.
+    fn depends_on(&self, id: &Uuid, other_id: &Uuid) -> bool {
+        let mut pending = vec![*id];
+        let mut visited = HashSet::new();
+
+        while let Some(current_id) = pending.pop() {
+            if !visited.insert(current_id) {
+                continue;
+            }
+
+            let Ok(task) = self.get(&current_id) else {
+                continue;
+            };
+            if task.get_blockers().contains(other_id) {
+                return true;
+            }
+            pending.extend(task.get_blockers().iter().copied());
+        }
+
+        false
+    }
+
+    /// Returns the blockers of the task that are not complete yet.
+    pub fn get_unfinished_blockers(&self, id: &Uuid) -> Result<Vec<&Task>, RusticError> {
+        let blockers = self.get(id)?
+            .get_blockers()
+            .iter()
+            .filter_map(|blocker_id| self.get(blocker_id).ok())
+            .filter(|blocker| blocker.get_status() != &Status::Complete)
+            .collect();
+
+        Ok(blockers)
+    }
+
+    /// Checks if the task has unfinished blockers.
+    pub fn is_blocked(&self, id: &Uuid) -> Result<bool, RusticError> {
+        Ok(!self.get_unfinished_blockers(id)?.is_empty())
+    }
+
+    /// Returns the tasks that are not complete and can be worked on.
+    pub fn get_actionable_tasks(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.get_status() != &Status::Complete)
+            .filter(|task| !self.is_blocked(task.get_id()).unwrap_or_default())
+            .collect()
+    }
+
+    /// Returns the tasks that are not complete and wait for their blockers.
+    pub fn get_blocked_tasks(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| task.get_status() != &Status::Complete)
+            .filter(|task| self.is_blocked(task.get_id()).unwrap_or_default())
+            .collect()
+    }
+
+    /// Updates the status of the task, which fails when a task
+    /// with unfinished blockers is started or completed.
+    pub fn update_status(&mut self, id: &Uuid, status: Status) -> Result<(), RusticError> {
+        match status {
+            Status::Pending => self.reopen(id),
+            Status::InProgress => {
+                if self.is_blocked(id)? {
+                    return Err(RusticError::TaskBlocked(*id));
+                }
+
+                self.reopen(id)?;
+                let task = self.get_mut(id)?;
+                task.update_status(Status::InProgress);
+
+                Ok(())
+            },
+            Status::Complete => self.complete(id).map(|_| ()),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::{io::Write, path::Path};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    utils::write_atomically,
+    Author,
+    Priority,
+    Recurrence,
+    Status,
+    Task,
+    TaskBuilder,
+    TaskEvent,
+    ToDoList,
+};
+use crate::RusticError;
+
+/// The version of the JSON document written by this version of the app.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// The versioned JSON document with the profile of the author and
+/// every task of the to-do list, which is a lossless backup.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonDocument {
+    schema_version: u64,
+    exported_on: DateTime<Local>,
+    author: AuthorProfile,
+    tasks: Vec<TaskRecord>,
+}
+
+/// The details of the author that are safe to share,
+/// which leave out the password hash.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AuthorProfile {
+    id: Uuid,
+    name: String,
+    email: String,
+    created_on: DateTime<Local>,
+}
+
+/// The task as written in the document and the journal, where the lists
+/// are JSON arrays instead of the comma-separated storage columns.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TaskRecord {
+    id: Uuid,
+    name: String,
+    description: Option<String>,
+    created_on: DateTime<Local>,
+    due_date: Option<DateTime<Local>>,
+    priority: Priority,
+    status: Status,
+    #[serde(default)]
+    labels: Vec<String>,
+    completed_on: Option<DateTime<Local>>,
+    recurrence: Option<Recurrence>,
+    parent: Option<Uuid>,
+    #[serde(default)]
+    subtasks: Vec<Uuid>,
+    #[serde(default)]
+    blocked_by: Vec<Uuid>,
+    history: Vec<TaskEvent>,
+}
+
+/// The part of the document that is read before the rest of it.
+#[derive(Deserialize)]
+struct SchemaHeader {
+    schema_version: u64,
+}
+
+impl JsonDocument {
+    /// Creates the document with the author and the tasks.
+    pub fn new(author: &Author, tasks: &[Task]) -> Self {
+        JsonDocument {
+            schema_version: SCHEMA_VERSION,
+            exported_on: Local::now(),
+            author: AuthorProfile::from(author),
+            tasks: tasks.iter().map(TaskRecord::from).collect(),
+        }
+    }
+
+    /// Returns the `schema_version` field.
+    pub fn get_schema_version(&self) -> u64 {
+        self.schema_version
+    }
+
+    /// Returns an immutable borrow to the `exported_on` field.
+    pub fn get_exported_on(&self) -> &DateTime<Local> {
+        &self.exported_on
+    }
+
+    /// Returns an immutable borrow to the `author` field.
+    pub fn get_author(&self) -> &AuthorProfile {
+        &self.author
+    }
+
+    /// Returns the tasks of the document, created by its author.
+    pub fn into_tasks(self) -> Vec<Task> {
+        let created_by = self.author.id.to_string();
+
+        self.tasks
+            .into_iter()
+            .map(|record| record.into_task(&created_by))
+            .collect()
+    }
+
+    /// Writes the document as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, RusticError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads the document from JSON, checking the schema version first.
+    ///
+    /// The documents of older versions are to be upgraded here, one version
+    /// at a time, whenever the schema changes, while the documents of the
+    /// unknown versions are rejected.
+    pub fn from_json(input: &str) -> Result<Self, RusticError> {
+        let mut value: serde_json::Value = serde_json::from_str(input)?;
+        let header = SchemaHeader::deserialize(&value)?;
+
+        match header.schema_version {
+            1 => upgrade_from_v1(&mut value)?,
+            SCHEMA_VERSION => {},
+            version => return Err(RusticError::UnsupportedSchemaVersion(version)),
+        }
+
+        Ok(JsonDocument::deserialize(&value)?)
+    }
+}
+
+impl AuthorProfile {
+    /// Returns an immutable borrow to the `id` field.
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    /// Returns an immutable borrow to the `name` field.
+    pub fn get_name(&self) -> &str {
+        &self.name[..]
+    }
+
+    /// Returns an immutable borrow to the `email` field.
+    pub fn get_email(&self) -> &str {
+        &self.email[..]
+    }
+
+    /// Returns an immutable borrow to the `created_on` field.
+    pub fn get_created_on(&self) -> &DateTime<Local> {
+        &self.created_on
+    }
+}
+
+impl From<&Author> for AuthorProfile {
+    fn from(author: &Author) -> Self {
+        AuthorProfile {
+            id: *author.get_id(),
+            name: author.get_name().clone(),
+            email: author.get_email().to_string(),
+            created_on: *author.get_created_on(),
+        }
+    }
+}
+
+impl From<&Task> for TaskRecord {
+    fn from(task: &Task) -> Self {
+        TaskRecord {
+            id: *task.get_id(),
+            name: task.get_name().to_string(),
+            description: task.get_description().map(str::to_string),
+            created_on: *task.get_created_on(),
+            due_date: task.get_due_date().copied(),
+            priority: *task.get_priority(),
+            status: *task.get_status(),
+            labels: task.get_labels().clone(),
+            completed_on: task.get_completed_on().copied(),
+            recurrence: task.get_recurrence().cloned(),
+            parent: task.get_parent().copied(),
+            subtasks: task.get_subtask_ids().clone(),
+            blocked_by: task.get_blockers().clone(),
+            history: task.get_history().clone(),
+        }
+    }
+}
+
+impl TaskRecord {
+    /// Returns an immutable borrow to the `id` field.
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    /// Returns the task of the record, created by the author.
+    pub fn into_task(self, created_by: &str) -> Task {
+        let mut task = TaskBuilder::new(created_by);
+        task.id(self.id)
+            .name(&self.name)
+            .description(self.description)
+            .created_on(self.created_on)
+            .due_date(self.due_date)
+            .priority(self.priority)
+            .status(self.status)
+            .labels(self.labels)
+            .completed_on(self.completed_on)
+            .recurrence(self.recurrence)
+            .parent(self.parent)
+            .subtasks(self.subtasks)
+            .blocked_by(self.blocked_by)
+            .history(self.history);
+
+        task.build()
+    }
+}
+
+impl ToDoList {
+    /// Returns the versioned JSON document of the to-do list.
+    pub fn to_json_document(&self) -> JsonDocument {
+        JsonDocument::new(&self.author, &self.tasks)
+    }
+
+    /// Writes the to-do list into a versioned JSON file.
+    pub fn write_to_json(&self, file_path: &Path) -> Result<(), RusticError> {
+        let document = self.to_json_document().to_json()?;
+
+        write_atomically(file_path, |file| {
+            file.write_all(document.as_bytes())?;
+            Ok(())
+        })
+    }
+}
+
+/// Reads the tasks of the versioned JSON document, created by its author.
+pub fn read_json(input: &str) -> Result<Vec<Task>, RusticError> {
+    Ok(JsonDocument::from_json(input)?.into_tasks())
+}
+
+/// Upgrades the document of version 1 to version 2, where
+/// the tasks gained the history of their changes.
+fn upgrade_from_v1(value: &mut serde_json::Value) -> Result<(), RusticError> {
+    let document = value.as_object_mut().ok_or_else(|| {
+        <serde_json::Error as serde::de::Error>::custom("the document is not an object")
+    })?;
+
+    if let Some(tasks) = document.get_mut("tasks").and_then(|tasks| tasks.as_array_mut()) {
+        for task in tasks {
+            if let Some(task) = task.as_object_mut() {
+                task.insert("history".to_string(), serde_json::Value::Array(Vec::new()));
+            }
+        }
+    }
+    document.insert("schema_version".to_string(), serde_json::Value::from(2));
+
+    Ok(())
+}
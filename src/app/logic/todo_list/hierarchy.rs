@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use super::{Status, Task, ToDoList};
+use crate::RusticError;
+
+impl ToDoList {
+    /// Adds a new task as the last subtask of the parent task. This cannot
+    /// be undone, unlike executing `TaskCommand::Add` with the parent.
+    pub fn add_subtask(&mut self, parent_id: &Uuid, mut task: Task) -> Result<(), RusticError> {
+        self.get_mut(parent_id)?.insert_subtask(usize::MAX, *task.get_id());
+        task.set_parent(Some(*parent_id));
+        self.add(task);
+
+        Ok(())
+    }
+
+    /// Moves the task to the index among its siblings, where
+    /// the indices past the last sibling move it to the end.
+    pub fn move_task(&mut self, id: &Uuid, index: usize) -> Result<(), RusticError> {
+        if let Some(parent_id) = self.get(id)?.get_parent().copied() {
+            if let Ok(parent) = self.get_mut(&parent_id) {
+                parent.remove_subtask(id);
+                parent.insert_subtask(index, *id);
+
+                return Ok(());
+            }
+        }
+
+        // The root tasks keep the order of the list, so put
+        // the task right before the root task at the index
+        let position = self.tasks
+            .iter()
+            .position(|task| task.get_id() == id)
+            .ok_or(RusticError::TaskNotFound(*id))?;
+        let task = self.tasks.remove(position);
+        let root_ids: Vec<Uuid> = self.get_root_tasks().iter().map(|task| *task.get_id()).collect();
+        let position = root_ids
+            .get(index)
+            .and_then(|root_id| self.tasks.iter().position(|task| task.get_id() == root_id))
+            .unwrap_or(self.tasks.len());
+        self.tasks.insert(position, task);
+
+        Ok(())
+    }
+
+    /// Returns the subtasks of the task in their order.
+    pub fn get_subtasks(&self, id: &Uuid) -> Result<Vec<&Task>, RusticError> {
+        let subtasks = self.get(id)?
+            .get_subtask_ids()
+            .iter()
+            .filter_map(|subtask_id| self.get(subtask_id).ok())
+            .collect();
+
+        Ok(subtasks)
+    }
+
+    /// Returns the tasks that are not subtasks of another task.
+    pub fn get_root_tasks(&self) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|task| match task.get_parent() {
+                Some(parent_id) => self.get(parent_id).is_err(),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Walks through the task tree depth-first and
+    /// returns every task along with its depth.
+    pub fn walk(&self) -> Vec<(usize, &Task)> {
+        let mut walked = Vec::new();
+        let mut visited = HashSet::new();
+        for task in self.get_root_tasks() {
+            self.walk_from(task, 0, &mut walked, &mut visited);
+        }
+
+        walked
+    }
+
+    /// Walks through the subtree of the task depth-first.
+    fn walk_from<'a>(
+        &'a self,
+        task: &'a Task,
+        depth: usize,
+        walked: &mut Vec<(usize, &'a Task)>,
+        visited: &mut HashSet<Uuid>,
+    ) {
+        // Guard against broken links that form a loop
+        if !visited.insert(*task.get_id()) {
+            return;
+        }
+
+        walked.push((depth, task));
+        for subtask_id in task.get_subtask_ids() {
+            if let Ok(subtask) = self.get(subtask_id) {
+                self.walk_from(subtask, depth + 1, walked, visited);
+            }
+        }
+    }
+
+    /// Returns the number of completed subtasks and the total number of subtasks.
+    pub fn get_progress(&self, id: &Uuid) -> Result<(usize, usize), RusticError> {
+        let subtasks = self.get_subtasks(id)?;
+        let completed = subtasks
+            .iter()
+            .filter(|subtask| subtask.get_status() == &Status::Complete)
+            .count();
+
+        Ok((completed, subtasks.len()))
+    }
+
+    /// Removes the task along with its subtasks and returns them in the
+    /// depth-first order. This cannot be undone, unlike executing `TaskCommand::Remove`.
+    pub fn remove_tree(&mut self, id: &Uuid) -> Result<Vec<Task>, RusticError> {
+        let task = self.get(id)?;
+
+        // Collect the ids of the subtree
+        let mut walked = Vec::new();
+        self.walk_from(task, 0, &mut walked, &mut HashSet::new());
+        let ids: Vec<Uuid> = walked.iter().map(|(_, task)| *task.get_id()).collect();
+
+        // Detach the task from its parent
+        if let Some(parent_id) = task.get_parent().copied() {
+            if let Ok(parent) = self.get_mut(&parent_id) {
+                parent.remove_subtask(id);
+            }
+        }
+
+        let mut removed = Vec::new();
+        for id in &ids {
+            if let Some(index) = self.tasks.iter().position(|task| task.get_id() == id) {
+                removed.push(self.tasks.remove(index));
+            }
+        }
+
+        // Unblock the tasks that waited for the removed ones
+        for task in &mut self.tasks {
+            task.remove_blockers(&ids);
+        }
+
+        Ok(removed)
+    }
+
+    /// Removes the tasks along with their subtasks, skipping the ones
+    /// removed as a subtask of an earlier one, and returns them.
+    pub(super) fn remove_trees(&mut self, ids: &[Uuid]) -> Vec<Task> {
+        let mut removed = Vec::new();
+        for id in ids {
+            if let Ok(tree) = self.remove_tree(id) {
+                removed.extend(tree);
+            }
+        }
+
+        removed
+    }
+
+    /// Completes the parent once every subtask is complete,
+    /// and reopens it once one of them is not.
+    pub(super) fn roll_up_completion(&mut self, id: &Uuid) -> Result<(), RusticError> {
+        let Some(parent_id) = self.get(id)?.get_parent().copied() else {
+            return Ok(());
+        };
+        let Ok(parent) = self.get(&parent_id) else {
+            return Ok(());
+        };
+
+        let (completed, total) = self.get_progress(&parent_id)?;
+        let parent_completed = parent.get_status() == &Status::Complete;
+
+        if completed == total && !parent_completed && !self.is_blocked(&parent_id)? {
+            self.complete(&parent_id)?;
+        } else if completed < total && parent_completed {
+            self.reopen(&parent_id)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+use std::{collections::HashMap, io::Write, path::Path};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use super::{utils::write_atomically, Priority, Recurrence, Status, Task, TaskBuilder, ToDoList};
+use crate::RusticError;
+
+/// The product identifier written into the calendars.
+const PRODUCT_ID: &str = "-//DracoY-code//RusticTasks//EN";
+
+/// The longest line in octets before it is folded.
+const LINE_LIMIT: usize = 75;
+
+/// Writes the tasks as the VTODO components of an iCalendar object.
+///
+/// The parents, the subtasks and the blockers are written as `RELATED-TO`
+/// with the `PARENT`, `CHILD` and `DEPENDS-ON` relation types, and the
+/// times are written in UTC to the second, which is the precision of
+/// the format.
+pub fn write_ical(tasks: &[Task]) -> String {
+    let mut output = String::new();
+    let stamp = format_date_time(&Local::now());
+
+    push_line(&mut output, "BEGIN:VCALENDAR");
+    push_line(&mut output, "VERSION:2.0");
+    push_line(&mut output, &format!("PRODID:{PRODUCT_ID}"));
+
+    for task in tasks {
+        push_line(&mut output, "BEGIN:VTODO");
+        push_line(&mut output, &format!("UID:{}", task.get_id()));
+        push_line(&mut output, &format!("DTSTAMP:{stamp}"));
+        push_line(&mut output, &format!("CREATED:{}", format_date_time(task.get_created_on())));
+        push_line(&mut output, &format!("SUMMARY:{}", escape_text(task.get_name())));
+        if let Some(description) = task.get_description() {
+            push_line(&mut output, &format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(due_date) = task.get_due_date() {
+            push_line(&mut output, &format!("DUE:{}", format_date_time(due_date)));
+        }
+        push_line(&mut output, &format!("PRIORITY:{}", priority_to_ical(task.get_priority())));
+        push_line(&mut output, &format!("STATUS:{}", status_to_ical(task.get_status())));
+        if !task.get_labels().is_empty() {
+            let labels: Vec<String> = task.get_labels().iter().map(|l| escape_text(l)).collect();
+            push_line(&mut output, &format!("CATEGORIES:{}", labels.join(",")));
+        }
+        if let Some(completed_on) = task.get_completed_on() {
+            push_line(&mut output, &format!("COMPLETED:{}", format_date_time(completed_on)));
+        }
+        if let Some(recurrence) = task.get_recurrence() {
+            push_line(&mut output, &format!("RRULE:{recurrence}"));
+        }
+        if let Some(parent) = task.get_parent() {
+            push_line(&mut output, &format!("RELATED-TO;RELTYPE=PARENT:{parent}"));
+        }
+        for subtask in task.get_subtask_ids() {
+            push_line(&mut output, &format!("RELATED-TO;RELTYPE=CHILD:{subtask}"));
+        }
+        for blocker in task.get_blockers() {
+            push_line(&mut output, &format!("RELATED-TO;RELTYPE=DEPENDS-ON:{blocker}"));
+        }
+        push_line(&mut output, "END:VTODO");
+    }
+
+    push_line(&mut output, "END:VCALENDAR");
+
+    output
+}
+
+/// Reads the tasks from the VTODO components of an iCalendar object,
+/// skipping the other components such as the events and the alarms.
+///
+/// The UIDs that are not UUIDs, as written by other calendar tools,
+/// are given new ids that the relations in the same object follow.
+pub fn read_ical(input: &str) -> Result<Vec<Task>, RusticError> {
+    let mut tasks = Vec::new();
+    let mut ids: HashMap<String, Uuid> = HashMap::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut task: Option<TaskBuilder> = None;
+
+    for (number, line) in unfold_lines(input) {
+        let invalid = |message: String| RusticError::InvalidICalendar(number, message);
+        let property = split_property(&line)
+            .ok_or_else(|| invalid(format!("'{line}' is not a property.")))?;
+        let value = &property.value;
+
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = value.to_ascii_uppercase();
+                if component == "VTODO" && components.is_empty() {
+                    return Err(invalid("The VTODO is not inside a VCALENDAR.".to_string()));
+                }
+                if component == "VTODO" && components.len() == 1 {
+                    let mut builder = TaskBuilder::new("");
+                    builder.id(Uuid::nil());
+                    task = Some(builder);
+                }
+                components.push(component);
+                continue;
+            },
+            "END" => {
+                let component = value.to_ascii_uppercase();
+                if components.pop().as_ref() != Some(&component) {
+                    return Err(invalid(format!("'{value}' was never begun.")));
+                }
+                if component == "VTODO" && components.len() == 1 {
+                    if let Some(mut task) = task.take() {
+                        // Give the tasks without a UID an id of their own
+                        if task.as_task().get_id().is_nil() {
+                            task.id(Uuid::new_v4());
+                        }
+                        tasks.push(task.build());
+                    }
+                }
+                continue;
+            },
+            _ => {},
+        }
+
+        // Only the properties of the VTODO itself are read
+        let Some(task) = task.as_mut().filter(|_| components.len() == 2) else {
+            continue;
+        };
+
+        match property.name.as_str() {
+            "UID" => {
+                task.id(resolve_id(&mut ids, value));
+            },
+            "CREATED" => {
+                task.created_on(parse_date_time(&property).ok_or_else(|| {
+                    invalid(format!("'{value}' is not a date and time."))
+                })?);
+            },
+            "SUMMARY" => {
+                task.name(&unescape_text(value));
+            },
+            "DESCRIPTION" => {
+                task.description(Some(unescape_text(value)));
+            },
+            "DUE" => {
+                task.due_date(Some(parse_date_time(&property).ok_or_else(|| {
+                    invalid(format!("'{value}' is not a date and time."))
+                })?));
+            },
+            "PRIORITY" => {
+                task.priority(value
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(priority_from_ical)
+                    .ok_or_else(|| invalid(format!("'{value}' is not a priority from 0 to 9.")))?);
+            },
+            "STATUS" => {
+                task.status(status_from_ical(value)
+                    .ok_or_else(|| invalid(format!("'{value}' is not a status of a VTODO.")))?);
+            },
+            "CATEGORIES" => {
+                for label in split_list(value).iter().map(|label| unescape_text(label)) {
+                    if !label.is_empty() {
+                        task.label(label);
+                    }
+                }
+            },
+            "COMPLETED" => {
+                task.completed_on(Some(parse_date_time(&property)
+                    .ok_or_else(|| invalid(format!("'{value}' is not a date and time.")))?));
+            },
+            "RRULE" => {
+                task.recurrence(Some(value.parse::<Recurrence>().map_err(|_| {
+                    invalid(format!("'{value}' is not a supported repetition rule."))
+                })?));
+            },
+            "RELATED-TO" => {
+                let id = resolve_id(&mut ids, value);
+                let relation = property.parameter("RELTYPE").map(str::to_ascii_uppercase);
+
+                // The relation is the parent unless it says otherwise
+                match relation.as_deref() {
+                    None | Some("PARENT") => {
+                        task.parent(Some(id));
+                    },
+                    Some("CHILD") => {
+                        task.subtask(id);
+                    },
+                    Some("DEPENDS-ON") => {
+                        task.blocker(id);
+                    },
+                    Some(_) => {},
+                }
+            },
+            _ => {},
+        }
+    }
+
+    if let Some(component) = components.last() {
+        return Err(RusticError::InvalidICalendar(
+            input.lines().count(),
+            format!("'{component}' is never ended."),
+        ));
+    }
+
+    Ok(tasks)
+}
+
+impl ToDoList {
+    /// Writes the to-do list into an iCalendar file.
+    pub fn write_to_ical(&self, file_path: &Path) -> Result<(), RusticError> {
+        let calendar = write_ical(&self.tasks);
+
+        write_atomically(file_path, |file| {
+            file.write_all(calendar.as_bytes())?;
+            Ok(())
+        })
+    }
+}
+
+/// A content line split into its parts.
+struct Property {
+    name: String,
+    parameters: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    /// Returns the value of the parameter with the uppercase key.
+    fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(other, _)| other == key)
+            .map(|(_, value)| &value[..])
+    }
+}
+
+/// Appends the content line, folding it into lines of at most 75 octets.
+fn push_line(output: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LIMIT {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// Joins the folded lines back together, along with
+/// the number of the line that each one starts on.
+fn unfold_lines(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, previous))) => previous.push_str(rest),
+            _ if line.trim().is_empty() => {},
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+
+    lines
+}
+
+/// Splits the content line into its uppercase name,
+/// its parameters and its value.
+fn split_property(line: &str) -> Option<Property> {
+    // The value starts at the first colon outside of the quotes
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        },
+        ':' if !in_quotes => Some(index),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let parameters = parts
+        .filter_map(|parameter| parameter.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+
+    Some(Property { name, parameters, value: value.to_string() })
+}
+
+/// Returns the id of the UID, giving a new id to the UIDs that are not UUIDs.
+fn resolve_id(ids: &mut HashMap<String, Uuid>, uid: &str) -> Uuid {
+    let uid = uid.trim();
+
+    *ids.entry(uid.to_string())
+        .or_insert_with(|| Uuid::parse_str(uid).unwrap_or_else(|_| Uuid::new_v4()))
+}
+
+/// Escapes the backslashes, the separators and the newlines of the text.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Reverses the escaping of the text.
+fn unescape_text(text: &str) -> String {
+    let mut output = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some(c) => output.push(c),
+            None => output.push('\\'),
+        }
+    }
+
+    output
+}
+
+/// Splits the list at the commas that are not escaped.
+fn split_list(value: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                items.push(&value[start..index]);
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    items.push(&value[start..]);
+
+    items
+}
+
+/// Formats the time in UTC, such as `20240610T103000Z`.
+fn format_date_time(date_time: &DateTime<Local>) -> String {
+    date_time.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Parses the time in UTC, the local time or the date, which
+/// is taken as the end of the day. The times with a time zone
+/// id are read as local times.
+fn parse_date_time(property: &Property) -> Option<DateTime<Local>> {
+    let value = property.value.trim();
+    let is_date = property
+        .parameter("VALUE")
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("DATE"));
+
+    if is_date || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let time = NaiveTime::from_hms_opt(23, 59, 59)?;
+        return Local.from_local_datetime(&date.and_time(time)).earliest();
+    }
+
+    if let Some(value) = value.strip_suffix(['Z', 'z']) {
+        let date_time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&date_time).with_timezone(&Local));
+    }
+
+    let date_time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local.from_local_datetime(&date_time).earliest()
+}
+
+/// Returns the iCalendar priority, where 1 is the highest and 9 the lowest.
+fn priority_to_ical(priority: &Priority) -> u32 {
+    match priority {
+        Priority::High => 1,
+        Priority::Medium => 5,
+        Priority::Low => 9,
+    }
+}
+
+/// Returns the priority of the iCalendar priority,
+/// where 0 means that it is not defined.
+fn priority_from_ical(priority: u32) -> Option<Priority> {
+    match priority {
+        0 | 5 => Some(Priority::Medium),
+        1..=4 => Some(Priority::High),
+        6..=9 => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// Returns the iCalendar status of the task.
+fn status_to_ical(status: &Status) -> &'static str {
+    match status {
+        Status::Pending => "NEEDS-ACTION",
+        Status::InProgress => "IN-PROCESS",
+        Status::Complete => "COMPLETED",
+    }
+}
+
+/// Returns the status of the iCalendar status,
+/// where the cancelled tasks are complete.
+fn status_from_ical(status: &str) -> Option<Status> {
+    match status.trim().to_ascii_uppercase().as_str() {
+        "NEEDS-ACTION" => Some(Status::Pending),
+        "IN-PROCESS" => Some(Status::InProgress),
+        "COMPLETED" | "CANCELLED" => Some(Status::Complete),
+        _ => None,
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    document::TaskRecord,
+    utils::write_atomically,
+    Author,
+    Status,
+    Task,
+    ToDoList,
+    UndoStack,
+};
+use crate::RusticError;
+
+/// The append-only journal of the changes made to the tasks, which are
+/// replayed to rebuild the tasks of an author at any point in time.
+///
+/// The journal is written as JSON Lines, one entry per line, so that
+/// saving the tasks only appends the entries of what has changed.
+#[derive(Clone, Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+/// The event recorded in the journal, along with when and by whom.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JournalEntry {
+    sequence: u64,
+    recorded_on: DateTime<Local>,
+    recorded_by: Uuid,
+    event: JournalEvent,
+}
+
+/// The changes to the tasks of an author, where the tasks are
+/// written as they were right after the change.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum JournalEvent {
+    TaskCreated { task: TaskRecord },
+    TaskUpdated { task: TaskRecord },
+    TaskCompleted { task: TaskRecord },
+    TaskDeleted { id: Uuid },
+    /// The tasks were put in the order of the ids
+    TasksReordered { ids: Vec<Uuid> },
+    /// Every task of the author, which replaces the entries before it
+    Snapshot { tasks: Vec<TaskRecord> },
+}
+
+impl Journal {
+    /// Reads the journal from the file, which is empty if it does not exist yet.
+    pub fn read_from(file_path: &Path) -> Result<Self, RusticError> {
+        // Nothing has been recorded yet
+        if !file_path.exists() {
+            return Ok(Journal::default());
+        }
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(fs::File::open(file_path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(Journal { entries })
+    }
+
+    /// Appends the entries to the end of the journal file.
+    pub fn append_to(file_path: &Path, entries: &[JournalEntry]) -> Result<(), RusticError> {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(file_path)?;
+
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())?;
+        file.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Rewrites the journal file with every entry, such as after compaction.
+    pub fn write_to(&self, file_path: &Path) -> Result<(), RusticError> {
+        write_atomically(file_path, |file| {
+            for entry in &self.entries {
+                serde_json::to_writer(&mut *file, entry)?;
+                file.write_all(b"\n")?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns an immutable borrow to the `entries` field.
+    pub fn get_entries(&self) -> &Vec<JournalEntry> {
+        &self.entries
+    }
+
+    /// Rebuilds the tasks of the author with the id from their entries recorded
+    /// up to `until`, or from every entry, starting from the latest snapshot.
+    pub fn replay(&self, author_id: &Uuid, until: Option<&DateTime<Local>>) -> Vec<Task> {
+        let entries: Vec<&JournalEntry> = self.entries
+            .iter()
+            .filter(|entry| &entry.recorded_by == author_id)
+            .filter(|entry| match until {
+                Some(until) => &entry.recorded_on <= until,
+                None => true,
+            })
+            .collect();
+
+        let start = entries
+            .iter()
+            .rposition(|entry| matches!(entry.event, JournalEvent::Snapshot { .. }))
+            .unwrap_or(0);
+
+        let mut tasks = Vec::new();
+        for entry in &entries[start..] {
+            entry.event.apply(&mut tasks, author_id);
+        }
+
+        tasks
+    }
+
+    /// Records the events that turn the journaled tasks of the author into
+    /// the tasks, and returns the new entries to append to the journal file.
+    pub fn record(&mut self, author: &Author, tasks: &[Task]) -> Vec<JournalEntry> {
+        let author_id = author.get_id();
+        let mut journaled = self.replay(author_id, None);
+        let mut events = Vec::new();
+
+        for task in tasks {
+            let record = TaskRecord::from(task);
+            let event = match journaled.iter().find(|other| other.get_id() == task.get_id()) {
+                None => JournalEvent::TaskCreated { task: record },
+                Some(other) if TaskRecord::from(other) == record => continue,
+                Some(other) if other.get_status() != &Status::Complete
+                    && task.get_status() == &Status::Complete =>
+                {
+                    JournalEvent::TaskCompleted { task: record }
+                },
+                Some(_) => JournalEvent::TaskUpdated { task: record },
+            };
+            events.push(event);
+        }
+
+        for other in &journaled {
+            if !tasks.iter().any(|task| task.get_id() == other.get_id()) {
+                events.push(JournalEvent::TaskDeleted { id: *other.get_id() });
+            }
+        }
+
+        // Keep the order of the tasks, which the other events leave alone
+        for event in &events {
+            event.apply(&mut journaled, author_id);
+        }
+        let ids: Vec<Uuid> = tasks.iter().map(|task| *task.get_id()).collect();
+        if journaled.iter().map(Task::get_id).ne(ids.iter()) {
+            events.push(JournalEvent::TasksReordered { ids });
+        }
+
+        let recorded_on = Local::now();
+        let entries: Vec<JournalEntry> = events
+            .into_iter()
+            .zip(self.next_sequence()..)
+            .map(|(event, sequence)| JournalEntry {
+                sequence,
+                recorded_on,
+                recorded_by: *author_id,
+                event,
+            })
+            .collect();
+        self.entries.extend(entries.iter().cloned());
+
+        entries
+    }
+
+    /// Records the snapshot of the journaled tasks of the author, which
+    /// the later replays start from, and returns the new entry.
+    pub fn snapshot(&mut self, author: &Author) -> JournalEntry {
+        let tasks = self.replay(author.get_id(), None);
+        let entry = JournalEntry {
+            sequence: self.next_sequence(),
+            recorded_on: Local::now(),
+            recorded_by: *author.get_id(),
+            event: JournalEvent::Snapshot { tasks: tasks.iter().map(TaskRecord::from).collect() },
+        };
+        self.entries.push(entry.clone());
+
+        entry
+    }
+
+    /// Replaces the entries recorded up to `until` with a snapshot for each
+    /// author, so the tasks before then can no longer be replayed, and
+    /// returns the number of removed entries.
+    pub fn compact(&mut self, until: &DateTime<Local>) -> usize {
+        let (old, new): (Vec<JournalEntry>, Vec<JournalEntry>) = self.entries
+            .drain(..)
+            .partition(|entry| &entry.recorded_on <= until);
+        let journal = Journal { entries: old };
+
+        // Keep the sequence and the time of the last entry of each author
+        let mut snapshots: Vec<JournalEntry> = Vec::new();
+        for entry in journal.entries.iter().rev() {
+            if snapshots.iter().any(|snapshot| snapshot.recorded_by == entry.recorded_by) {
+                continue;
+            }
+
+            let tasks = journal.replay(&entry.recorded_by, None);
+            snapshots.push(JournalEntry {
+                event: JournalEvent::Snapshot { tasks: tasks.iter().map(TaskRecord::from).collect() },
+                ..entry.clone()
+            });
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.sequence);
+
+        let removed = journal.entries.len() - snapshots.len();
+        self.entries = snapshots;
+        self.entries.extend(new);
+
+        removed
+    }
+
+    /// Returns the sequence number of the next entry.
+    fn next_sequence(&self) -> u64 {
+        self.entries.last().map_or(1, |entry| entry.sequence + 1)
+    }
+}
+
+impl JournalEntry {
+    /// Returns the `sequence` field.
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns an immutable borrow to the `recorded_on` field.
+    pub fn get_recorded_on(&self) -> &DateTime<Local> {
+        &self.recorded_on
+    }
+
+    /// Returns an immutable borrow to the `recorded_by` field,
+    /// which holds the id of the author.
+    pub fn get_recorded_by(&self) -> &Uuid {
+        &self.recorded_by
+    }
+
+    /// Returns an immutable borrow to the `event` field.
+    pub fn get_event(&self) -> &JournalEvent {
+        &self.event
+    }
+}
+
+impl JournalEvent {
+    /// Applies the event to the tasks of the author with the id.
+    fn apply(&self, tasks: &mut Vec<Task>, author_id: &Uuid) {
+        let created_by = author_id.to_string();
+
+        match self {
+            JournalEvent::TaskCreated { task }
+            | JournalEvent::TaskUpdated { task }
+            | JournalEvent::TaskCompleted { task } => {
+                let task = task.clone().into_task(&created_by);
+                match tasks.iter_mut().find(|other| other.get_id() == task.get_id()) {
+                    Some(other) => *other = task,
+                    None => tasks.push(task),
+                }
+            },
+            JournalEvent::TaskDeleted { id } => tasks.retain(|task| task.get_id() != id),
+            JournalEvent::TasksReordered { ids } => {
+                // Fill the places of the listed tasks in the order of the ids
+                let places: Vec<usize> = tasks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, task)| ids.contains(task.get_id()))
+                    .map(|(index, _)| index)
+                    .collect();
+                let ordered: Vec<Task> = ids
+                    .iter()
+                    .filter_map(|id| tasks.iter().find(|task| task.get_id() == id).cloned())
+                    .collect();
+                for (index, task) in places.into_iter().zip(ordered) {
+                    tasks[index] = task;
+                }
+            },
+            JournalEvent::Snapshot { tasks: records } => {
+                *tasks = records
+                    .iter()
+                    .map(|record| record.clone().into_task(&created_by))
+                    .collect();
+            },
+        }
+    }
+}
+
+impl ToDoList {
+    /// Rebuilds the to-do list of the author from the journal as it was
+    /// at `until`, or as it is now.
+    pub fn replay(author: Author, journal: &Journal, until: Option<&DateTime<Local>>) -> Self {
+        let tasks = journal.replay(author.get_id(), until);
+
+        ToDoList { author, tasks, undo_stack: UndoStack::default() }
+    }
+}
@@ -0,0 +1,237 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use super::{Priority, Status, Task};
+use crate::RusticError;
+
+/// The comparison applied by the ordered filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Checks if the ordering of the left side
+    /// against the right side satisfies the comparison.
+    pub fn holds<T: PartialOrd>(&self, left: &T, right: &T) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+/// A single condition of the query.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    Status(Status),
+    Priority(Comparison, Priority),
+    Label(String),
+    Due(Comparison, NaiveDate),
+    Text(String),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Checks if the task satisfies the condition.
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Filter::Status(status) => task.get_status() == status,
+            Filter::Priority(comparison, priority) => {
+                comparison.holds(&task.get_priority().value(), &priority.value())
+            },
+            Filter::Label(label) => task
+                .get_labels()
+                .iter()
+                .any(|other| other.to_lowercase() == label.to_lowercase()),
+            Filter::Due(comparison, date) => task
+                .get_due_date()
+                .is_some_and(|due_date| comparison.holds(&due_date.date_naive(), date)),
+            Filter::Text(text) => {
+                let text = text.to_lowercase();
+                task.get_name().to_lowercase().contains(&text)
+                    || task
+                        .get_description()
+                        .is_some_and(|description| description.to_lowercase().contains(&text))
+            },
+            Filter::Not(filter) => !filter.matches(task),
+        }
+    }
+}
+
+/// The parsed query, which matches the tasks
+/// that satisfy every one of its filters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    filters: Vec<Filter>,
+}
+
+impl Query {
+    /// Returns an immutable borrow to the `filters` field.
+    pub fn get_filters(&self) -> &Vec<Filter> {
+        &self.filters
+    }
+
+    /// Checks if the task satisfies every filter of the query.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.filters.iter().all(|filter| filter.matches(task))
+    }
+}
+
+impl FromStr for Query {
+    type Err = RusticError;
+
+    /// Parses a query such as `status:pending priority:>=medium
+    /// label:Work due:<2024-07-01 "presentation"`, where the
+    /// filters prefixed with `-` are negated.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let filters = tokenize(input)?
+            .into_iter()
+            .map(parse_filter)
+            .collect::<Result<Vec<Filter>, RusticError>>()?;
+
+        Ok(Query { filters })
+    }
+}
+
+/// A whitespace-separated part of the query along with its position.
+struct Token {
+    position: usize,
+    negated: bool,
+    key: Option<String>,
+    value: String,
+    value_position: usize,
+}
+
+/// Splits the query into its tokens, keeping the quoted text together.
+fn tokenize(input: &str) -> Result<Vec<Token>, RusticError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        if chars[index].is_whitespace() {
+            index += 1;
+            continue;
+        }
+
+        let position = index;
+        let negated = chars[index] == '-';
+        if negated {
+            index += 1;
+        }
+
+        let mut key = None;
+        let mut value = String::new();
+        let mut value_position = index;
+
+        while index < chars.len() && !chars[index].is_whitespace() {
+            match chars[index] {
+                '"' => {
+                    // Read the quoted text up to the closing quote
+                    let quote_position = index;
+                    index += 1;
+                    while index < chars.len() && chars[index] != '"' {
+                        value.push(chars[index]);
+                        index += 1;
+                    }
+                    if index == chars.len() {
+                        return Err(RusticError::InvalidQuery(
+                            quote_position,
+                            "The quote is never closed.".to_string(),
+                        ));
+                    }
+                },
+                ':' if key.is_none() && !value.is_empty() => {
+                    key = Some(std::mem::take(&mut value).to_lowercase());
+                    value_position = index + 1;
+                },
+                c => value.push(c),
+            }
+            index += 1;
+        }
+
+        tokens.push(Token { position, negated, key, value, value_position });
+    }
+
+    Ok(tokens)
+}
+
+/// Parses the token into its filter.
+fn parse_filter(token: Token) -> Result<Filter, RusticError> {
+    let Token { position, negated, key, value, value_position } = token;
+    let invalid = |message: String| RusticError::InvalidQuery(value_position, message);
+
+    if value.is_empty() {
+        return Err(invalid("The filter needs a value.".to_string()));
+    }
+
+    let filter = match key.as_deref() {
+        None => Filter::Text(value),
+        Some("status") => Filter::Status(parse_status(&value).ok_or_else(|| {
+            invalid(format!("'{value}' is not a status."))
+        })?),
+        Some("priority") => {
+            let (comparison, value) = split_comparison(&value);
+            let priority = Priority::from_name(value).ok_or_else(|| {
+                invalid(format!("'{value}' is not a priority."))
+            })?;
+            Filter::Priority(comparison, priority)
+        },
+        Some("label") => Filter::Label(value),
+        Some("due") => {
+            let (comparison, value) = split_comparison(&value);
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                invalid(format!("'{value}' is not a date in the YYYY-MM-DD format."))
+            })?;
+            Filter::Due(comparison, date)
+        },
+        Some(key) => {
+            return Err(RusticError::InvalidQuery(
+                position + usize::from(negated),
+                format!("'{key}' is not a known filter."),
+            ));
+        },
+    };
+
+    if negated {
+        Ok(Filter::Not(Box::new(filter)))
+    } else {
+        Ok(filter)
+    }
+}
+
+/// Splits the comparison operator from the start of the value.
+fn split_comparison(value: &str) -> (Comparison, &str) {
+    for (operator, comparison) in [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ] {
+        if let Some(rest) = value.strip_prefix(operator) {
+            return (comparison, rest);
+        }
+    }
+
+    (Comparison::Equal, value)
+}
+
+/// Parses the status from its name.
+fn parse_status(value: &str) -> Option<Status> {
+    match value.to_lowercase().replace(['-', '_'], "").as_str() {
+        "pending" | "todo" => Some(Status::Pending),
+        "inprogress" | "started" => Some(Status::InProgress),
+        "complete" | "completed" | "done" => Some(Status::Complete),
+        _ => None,
+    }
+}
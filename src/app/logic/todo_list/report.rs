@@ -0,0 +1,203 @@
+use chrono::{DateTime, Local};
+
+use super::{DueBucket, Filter, SortKey, SortOrder, Status, Task, ToDoList};
+
+/// The styles of the HTML report, which keep it readable without any other files.
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+h1 { margin-bottom: 0.25rem; }
+.summary { color: #666; margin-top: 0; }
+h2 { border-bottom: 1px solid #ddd; padding-bottom: 0.25rem; }
+ul { list-style: none; padding: 0; }
+li { padding: 0.5rem 0.75rem; margin: 0.25rem 0; border-left: 4px solid #ddd; background: #fafafa; }
+li.overdue { border-left-color: #c0392b; background: #fdecea; }
+.complete .name { text-decoration: line-through; color: #888; }
+.details { color: #555; font-size: 0.9rem; }
+.overdue .due { color: #c0392b; font-weight: bold; }
+.label { background: #e8eef7; border-radius: 0.5rem; padding: 0 0.4rem; margin-right: 0.25rem; }
+.description { margin: 0.25rem 0 0; white-space: pre-wrap; }
+";
+
+/// The options that pick the tasks of a report and their order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReportOptions {
+    /// Only include the tasks with the status
+    pub status: Option<Status>,
+    /// Only include the tasks with the label, ignoring the case
+    pub label: Option<String>,
+    pub sort_key: SortKey,
+    pub sort_order: SortOrder,
+}
+
+impl ReportOptions {
+    /// Checks if the task passes the filters of the options.
+    pub fn includes(&self, task: &Task) -> bool {
+        if let Some(status) = self.status {
+            if !Filter::Status(status).matches(task) {
+                return false;
+            }
+        }
+        if let Some(label) = &self.label {
+            if !Filter::Label(label.clone()).matches(task) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl ToDoList {
+    /// Returns the tasks of the report in their order.
+    pub fn report_tasks(&self, options: &ReportOptions) -> Vec<&Task> {
+        self.sorted(options.sort_key, options.sort_order)
+            .into_iter()
+            .filter(|task| options.includes(task))
+            .collect()
+    }
+
+    /// Renders the tasks as a Markdown checklist, where the
+    /// unfinished tasks that are past their due date are marked.
+    pub fn to_markdown(&self, options: &ReportOptions, now: &DateTime<Local>) -> String {
+        let mut output = format!("# Tasks of {}\n\n", escape_markdown(self.author.get_name()));
+
+        let tasks = self.report_tasks(options);
+        if tasks.is_empty() {
+            output.push_str("_No tasks._\n");
+        }
+
+        for task in tasks {
+            let mark = if task.get_status() == &Status::Complete { 'x' } else { ' ' };
+            let mut details = vec![format!("{} priority", task.get_priority())];
+            if task.get_status() == &Status::InProgress {
+                details.push("in progress".to_string());
+            }
+            if let Some(due_date) = task.get_due_date() {
+                details.push(format!("due {}", due_date.format("%Y-%m-%d %H:%M")));
+            }
+            if is_overdue(task, now) {
+                details.push("**overdue**".to_string());
+            }
+            if !task.get_labels().is_empty() {
+                let labels: Vec<String> = task
+                    .get_labels()
+                    .iter()
+                    .map(|label| format!("`{}`", label.replace('`', "'")))
+                    .collect();
+                details.push(labels.join(" "));
+            }
+
+            output.push_str(&format!(
+                "- [{mark}] {} — {}\n",
+                escape_markdown(task.get_name()),
+                details.join(", "),
+            ));
+
+            // Indent the description under its item
+            if let Some(description) = task.get_description() {
+                for line in description.lines() {
+                    output.push_str(&format!("  {}\n", escape_markdown(line)));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Renders the tasks as a standalone HTML page grouped by the status,
+    /// where the unfinished tasks that are past their due date are highlighted.
+    pub fn to_html(&self, options: &ReportOptions, now: &DateTime<Local>) -> String {
+        let title = format!("Tasks of {}", escape_html(self.author.get_name()));
+        let tasks = self.report_tasks(options);
+        let overdue = tasks.iter().filter(|task| is_overdue(task, now)).count();
+
+        let mut output = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n\
+            <h1>{title}</h1>\n<p class=\"summary\">Generated on {} · {} tasks · {overdue} overdue</p>\n",
+            now.format("%Y-%m-%d %H:%M"),
+            tasks.len(),
+        );
+
+        for status in [Status::Pending, Status::InProgress, Status::Complete] {
+            let group: Vec<&&Task> = tasks.iter().filter(|task| task.get_status() == &status).collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            output.push_str(&format!("<section>\n<h2>{status} ({})</h2>\n<ul>\n", group.len()));
+            for task in group {
+                let mut classes = vec!["task"];
+                if status == Status::Complete {
+                    classes.push("complete");
+                }
+                if is_overdue(task, now) {
+                    classes.push("overdue");
+                }
+
+                output.push_str(&format!(
+                    "<li class=\"{}\">\n<span class=\"name\">{}</span>\n<div class=\"details\">\
+                    <span class=\"priority\">{} priority</span>",
+                    classes.join(" "),
+                    escape_html(task.get_name()),
+                    task.get_priority(),
+                ));
+                if let Some(due_date) = task.get_due_date() {
+                    let overdue = if is_overdue(task, now) { " (overdue)" } else { "" };
+                    output.push_str(&format!(
+                        " · <span class=\"due\">due {}{overdue}</span>",
+                        due_date.format("%Y-%m-%d %H:%M"),
+                    ));
+                }
+                for label in task.get_labels() {
+                    output.push_str(&format!(" <span class=\"label\">{}</span>", escape_html(label)));
+                }
+                output.push_str("</div>\n");
+
+                if let Some(description) = task.get_description() {
+                    output.push_str(&format!(
+                        "<p class=\"description\">{}</p>\n",
+                        escape_html(description),
+                    ));
+                }
+                output.push_str("</li>\n");
+            }
+            output.push_str("</ul>\n</section>\n");
+        }
+
+        if tasks.is_empty() {
+            output.push_str("<p>No tasks.</p>\n");
+        }
+        output.push_str("</body>\n</html>\n");
+
+        output
+    }
+}
+
+/// Checks if the task is unfinished and past its due date.
+fn is_overdue(task: &Task, now: &DateTime<Local>) -> bool {
+    task.get_status() != &Status::Complete
+        && DueBucket::of(task.get_due_date(), now) == DueBucket::Overdue
+}
+
+/// Escapes the characters that Markdown would read as formatting.
+fn escape_markdown(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+
+    output
+}
+
+/// Escapes the characters that HTML would read as markup.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -0,0 +1,42 @@
+use super::{Author, Storage, ToDoList};
+use crate::RusticError;
+
+/// The session of the logged-in author.
+#[derive(Debug)]
+pub struct Session {
+    todo_list: ToDoList,
+}
+
+impl Session {
+    /// Starts a session for the author and loads their to-do list.
+    pub fn start(author: Author, storage: &dyn Storage) -> Result<Self, RusticError> {
+        let todo_list = ToDoList::load(author, storage)?;
+
+        Ok(Session { todo_list })
+    }
+
+    /// Returns an immutable borrow to the logged-in author.
+    pub fn get_author(&self) -> &Author {
+        self.todo_list.get_author()
+    }
+
+    /// Returns an immutable borrow to the `todo_list` field.
+    pub fn get_todo_list(&self) -> &ToDoList {
+        &self.todo_list
+    }
+
+    /// Returns a mutable borrow to the `todo_list` field.
+    pub fn get_todo_list_mut(&mut self) -> &mut ToDoList {
+        &mut self.todo_list
+    }
+
+    /// Saves the to-do list of the session.
+    pub fn save(&self, storage: &dyn Storage) -> Result<(), RusticError> {
+        self.todo_list.save(storage)
+    }
+
+    /// Saves the to-do list and ends the session.
+    pub fn end(self, storage: &dyn Storage) -> Result<(), RusticError> {
+        self.save(storage)
+    }
+}
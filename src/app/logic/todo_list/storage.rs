@@ -0,0 +1,44 @@
+/// The module that implements the CSV storage backend.
+pub mod csv_storage;
+pub use csv_storage::CsvStorage;
+
+/// The module that implements the journal storage backend.
+pub mod journal_storage;
+pub use journal_storage::JournalStorage;
+
+/// The module that implements the JSON storage backend.
+pub mod json_storage;
+pub use json_storage::JsonStorage;
+
+/// The module that implements the in-memory storage backend.
+pub mod memory_storage;
+pub use memory_storage::MemoryStorage;
+
+/// The module that implements the SQLite storage backend.
+pub mod sqlite_storage;
+pub use sqlite_storage::SqliteStorage;
+
+use super::{Author, Task};
+use crate::RusticError;
+
+/// The interface shared by the backends that persist the authors and tasks.
+pub trait Storage {
+    /// Loads every saved author.
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError>;
+
+    /// Saves a new author, failing with `AuthorExists` if the email is taken.
+    fn save_author(&self, author: &Author) -> Result<(), RusticError>;
+
+    /// Loads the tasks created by the author.
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError>;
+
+    /// Saves the tasks of the author, replacing the previously saved ones.
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError>;
+
+    /// Finds the saved author with the given email.
+    fn find_author(&self, email: &str) -> Result<Option<Author>, RusticError> {
+        Ok(self.load_authors()?
+            .into_iter()
+            .find(|author| author.get_email() == email))
+    }
+}
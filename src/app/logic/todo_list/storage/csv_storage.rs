@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+
+use super::Storage;
+use crate::app::todo_list::utils::{
+    read_authors_from_csv,
+    read_tasks_from_csv,
+    rewrite_tasks_to_csv,
+    save_tasks_to_csv,
+    upgrade_task_owners,
+};
+use crate::{Author, RusticError, Task};
+
+/// The storage backend that keeps the authors
+/// and the tasks in two separate CSV files.
+#[derive(Debug)]
+pub struct CsvStorage {
+    authors_path: PathBuf,
+    tasks_path: PathBuf,
+}
+
+impl CsvStorage {
+    /// Creates a new CSV storage with the given file paths.
+    pub fn new(authors_path: &Path, tasks_path: &Path) -> Self {
+        CsvStorage {
+            authors_path: authors_path.to_path_buf(),
+            tasks_path: tasks_path.to_path_buf(),
+        }
+    }
+
+    /// Returns an immutable borrow to the `authors_path` field.
+    pub fn get_authors_path(&self) -> &Path {
+        &self.authors_path
+    }
+
+    /// Returns an immutable borrow to the `tasks_path` field.
+    pub fn get_tasks_path(&self) -> &Path {
+        &self.tasks_path
+    }
+}
+
+impl Storage for CsvStorage {
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError> {
+        read_authors_from_csv(&self.authors_path)
+    }
+
+    fn save_author(&self, author: &Author) -> Result<(), RusticError> {
+        author.write_to_csv(&self.authors_path)
+    }
+
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError> {
+        let mut tasks = read_tasks_from_csv(&self.tasks_path)?;
+
+        // Rewrite the files that still keep the tasks by the names of the authors
+        if upgrade_task_owners(&mut tasks, &self.load_authors()?) {
+            rewrite_tasks_to_csv(&tasks, &self.tasks_path)?;
+        }
+
+        Ok(tasks.into_iter().filter(|task| task.is_created_by(author)).collect())
+    }
+
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError> {
+        save_tasks_to_csv(author, tasks, &self.tasks_path)
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+use super::Storage;
+use crate::app::todo_list::utils::read_authors_from_csv;
+use crate::{Author, Journal, RusticError, Task};
+
+/// The storage backend that keeps the authors in a CSV file and the
+/// changes to the tasks in an append-only journal, which only grows by
+/// the changes since the last save.
+#[derive(Debug)]
+pub struct JournalStorage {
+    authors_path: PathBuf,
+    journal_path: PathBuf,
+}
+
+impl JournalStorage {
+    /// Creates a new journal storage with the given file paths.
+    pub fn new(authors_path: &Path, journal_path: &Path) -> Self {
+        JournalStorage {
+            authors_path: authors_path.to_path_buf(),
+            journal_path: journal_path.to_path_buf(),
+        }
+    }
+
+    /// Returns an immutable borrow to the `authors_path` field.
+    pub fn get_authors_path(&self) -> &Path {
+        &self.authors_path
+    }
+
+    /// Returns an immutable borrow to the `journal_path` field.
+    pub fn get_journal_path(&self) -> &Path {
+        &self.journal_path
+    }
+
+    /// Reads the whole journal from the file.
+    pub fn read_journal(&self) -> Result<Journal, RusticError> {
+        Journal::read_from(&self.journal_path)
+    }
+
+    /// Loads the tasks created by the author as they were at the time.
+    pub fn load_tasks_at(
+        &self,
+        author: &Author,
+        at: &DateTime<Local>,
+    ) -> Result<Vec<Task>, RusticError> {
+        Ok(self.read_journal()?.replay(author.get_id(), Some(at)))
+    }
+
+    /// Records the snapshot of the tasks of the author,
+    /// which speeds up loading them.
+    pub fn snapshot(&self, author: &Author) -> Result<(), RusticError> {
+        let entry = self.read_journal()?.snapshot(author);
+
+        Journal::append_to(&self.journal_path, &[entry])
+    }
+
+    /// Compacts the entries recorded up to `until` into snapshots and
+    /// rewrites the journal, returning the number of removed entries.
+    pub fn compact(&self, until: &DateTime<Local>) -> Result<usize, RusticError> {
+        let mut journal = self.read_journal()?;
+        let removed = journal.compact(until);
+        journal.write_to(&self.journal_path)?;
+
+        Ok(removed)
+    }
+}
+
+impl Storage for JournalStorage {
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError> {
+        read_authors_from_csv(&self.authors_path)
+    }
+
+    fn save_author(&self, author: &Author) -> Result<(), RusticError> {
+        author.write_to_csv(&self.authors_path)
+    }
+
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError> {
+        Ok(self.read_journal()?.replay(author.get_id(), None))
+    }
+
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError> {
+        let entries = self.read_journal()?.record(author, tasks);
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        Journal::append_to(&self.journal_path, &entries)
+    }
+}
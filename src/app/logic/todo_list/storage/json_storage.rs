@@ -0,0 +1,90 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use super::Storage;
+use crate::app::todo_list::utils::write_atomically;
+use crate::{Author, RusticError, Task};
+
+/// The storage backend that keeps the authors
+/// and the tasks in a single JSON file.
+#[derive(Debug)]
+pub struct JsonStorage {
+    file_path: PathBuf,
+}
+
+/// The contents of the JSON file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct JsonDatabase {
+    authors: Vec<Author>,
+    tasks: Vec<Task>,
+}
+
+impl JsonStorage {
+    /// Creates a new JSON storage with the given file path.
+    pub fn new(file_path: &Path) -> Self {
+        JsonStorage { file_path: file_path.to_path_buf() }
+    }
+
+    /// Returns an immutable borrow to the `file_path` field.
+    pub fn get_file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Reads the whole database from the file.
+    fn read(&self) -> Result<JsonDatabase, RusticError> {
+        // Nothing has been saved yet
+        if !self.file_path.exists() {
+            return Ok(JsonDatabase::default());
+        }
+
+        let file = fs::File::open(&self.file_path)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Rewrites the whole database into the file.
+    fn write(&self, database: &JsonDatabase) -> Result<(), RusticError> {
+        write_atomically(&self.file_path, |file| {
+            serde_json::to_writer_pretty(file, database)?;
+            Ok(())
+        })
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError> {
+        Ok(self.read()?.authors)
+    }
+
+    fn save_author(&self, author: &Author) -> Result<(), RusticError> {
+        let mut database = self.read()?;
+
+        // Check if the author already exists
+        if database.authors.iter().any(|a| a.get_email() == author.get_email()) {
+            return Err(RusticError::AuthorExists);
+        }
+
+        database.authors.push(author.clone());
+        self.write(&database)
+    }
+
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError> {
+        let tasks = self.read()?
+            .tasks
+            .into_iter()
+            .filter(|task| task.is_created_by(author))
+            .collect();
+
+        Ok(tasks)
+    }
+
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError> {
+        let mut database = self.read()?;
+
+        // Replace the previously saved tasks of the author
+        database.tasks.retain(|task| !task.is_created_by(author));
+        database.tasks.extend(tasks.iter().cloned());
+
+        self.write(&database)
+    }
+}
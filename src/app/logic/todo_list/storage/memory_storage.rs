@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+
+use super::Storage;
+use crate::{Author, RusticError, Task};
+
+/// The storage backend that keeps everything in memory,
+/// which is mostly useful for tests.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    authors: RefCell<Vec<Author>>,
+    tasks: RefCell<Vec<Task>>,
+}
+
+impl MemoryStorage {
+    /// Creates a new empty in-memory storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError> {
+        Ok(self.authors.borrow().clone())
+    }
+
+    fn save_author(&self, author: &Author) -> Result<(), RusticError> {
+        let mut authors = self.authors.borrow_mut();
+
+        // Check if the author already exists
+        if authors.iter().any(|a| a.get_email() == author.get_email()) {
+            return Err(RusticError::AuthorExists);
+        }
+
+        authors.push(author.clone());
+        Ok(())
+    }
+
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError> {
+        let tasks = self.tasks
+            .borrow()
+            .iter()
+            .filter(|task| task.is_created_by(author))
+            .cloned()
+            .collect();
+
+        Ok(tasks)
+    }
+
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError> {
+        let mut saved_tasks = self.tasks.borrow_mut();
+
+        // Replace the previously saved tasks of the author
+        saved_tasks.retain(|task| !task.is_created_by(author));
+        saved_tasks.extend(tasks.iter().cloned());
+
+        Ok(())
+    }
+}
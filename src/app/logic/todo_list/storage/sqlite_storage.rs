@@ -0,0 +1,578 @@
+use std::path::Path;
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Row};
+use uuid::Uuid;
+
+use super::{CsvStorage, Storage};
+use crate::app::todo_list::utils::{read_tasks_from_csv, upgrade_task_owners};
+use crate::app::todo_list::TaskBuilder;
+use crate::{Author, Priority, Recurrence, RusticError, Status, Task, TaskEvent, TaskField};
+
+/// The schema migrations, where the migration at index `i`
+/// upgrades the database from version `i` to version `i + 1`.
+const MIGRATIONS: &[&str] = &[
+    // Version 1: authors, tasks and their labels
+    "CREATE TABLE authors (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        email TEXT NOT NULL UNIQUE,
+        password TEXT NOT NULL,
+        created_on TEXT NOT NULL
+    );
+
+    CREATE TABLE tasks (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT,
+        created_on TEXT NOT NULL,
+        due_date TEXT,
+        priority INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        completed_on TEXT,
+        created_by TEXT NOT NULL
+    );
+
+    CREATE TABLE task_labels (
+        task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        label TEXT NOT NULL,
+        PRIMARY KEY (task_id, position)
+    );
+
+    CREATE INDEX tasks_by_author ON tasks(created_by, position);
+    CREATE INDEX tasks_by_status ON tasks(created_by, status);
+    CREATE INDEX tasks_by_due_date ON tasks(created_by, due_date);
+    CREATE INDEX task_labels_by_label ON task_labels(label);",
+    // Version 2: the repetition rules of recurring tasks
+    "ALTER TABLE tasks ADD COLUMN recurrence TEXT;",
+    // Version 3: the parents and the ordered subtasks of tasks
+    "ALTER TABLE tasks ADD COLUMN parent TEXT;
+
+    CREATE TABLE task_subtasks (
+        task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        subtask_id TEXT NOT NULL,
+        PRIMARY KEY (task_id, position)
+    );
+
+    CREATE INDEX tasks_by_parent ON tasks(parent);",
+    // Version 4: the tasks blocking other tasks
+    "CREATE TABLE task_blockers (
+        task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        blocker_id TEXT NOT NULL,
+        PRIMARY KEY (task_id, position)
+    );
+
+    CREATE INDEX task_blockers_by_blocker ON task_blockers(blocker_id);",
+    // Version 5: the history of changes made to tasks
+    "CREATE TABLE task_events (
+        task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        changed_on TEXT NOT NULL,
+        changed_by TEXT NOT NULL,
+        field TEXT NOT NULL,
+        old_value TEXT,
+        new_value TEXT,
+        PRIMARY KEY (task_id, position)
+    );
+
+    CREATE INDEX task_events_by_changed_on ON task_events(changed_on);",
+    // Version 6: the timestamps written to the microsecond, padded to the nanosecond
+    "UPDATE authors SET created_on = substr(created_on, 1, 26) || '000Z'
+        WHERE length(created_on) = 27;
+    UPDATE tasks SET created_on = substr(created_on, 1, 26) || '000Z'
+        WHERE length(created_on) = 27;
+    UPDATE tasks SET due_date = substr(due_date, 1, 26) || '000Z'
+        WHERE length(due_date) = 27;
+    UPDATE tasks SET completed_on = substr(completed_on, 1, 26) || '000Z'
+        WHERE length(completed_on) = 27;
+    UPDATE task_events SET changed_on = substr(changed_on, 1, 26) || '000Z'
+        WHERE length(changed_on) = 27;",
+    // Version 7: the tasks kept by the id of their author instead of the name,
+    // where the tasks of a shared name go to the earliest of the authors
+    "UPDATE tasks SET created_by = (
+        SELECT id FROM authors WHERE authors.name = tasks.created_by
+        ORDER BY created_on, id LIMIT 1
+    )
+    WHERE created_by IN (SELECT name FROM authors);",
+    // Version 8: the changes kept by the id of their author instead of the name
+    "UPDATE task_events SET changed_by = (
+        SELECT id FROM authors WHERE authors.name = task_events.changed_by
+        ORDER BY created_on, id LIMIT 1
+    )
+    WHERE changed_by IN (SELECT name FROM authors);",
+];
+
+/// The columns selected when loading a task.
+const TASK_COLUMNS: &str = "id, name, description, created_on, due_date, \
+    priority, status, completed_on, created_by, recurrence, parent";
+
+/// The storage backend that keeps the authors
+/// and the tasks in an embedded SQLite database.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    /// Opens the database file and migrates it to the latest schema.
+    pub fn open(file_path: &Path) -> Result<Self, RusticError> {
+        Self::from_connection(Connection::open(file_path)?)
+    }
+
+    /// Opens a temporary in-memory database.
+    pub fn open_in_memory() -> Result<Self, RusticError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Sets up the connection and runs the pending migrations.
+    fn from_connection(connection: Connection) -> Result<Self, RusticError> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+
+        let storage = SqliteStorage { connection };
+        storage.migrate()?;
+
+        Ok(storage)
+    }
+
+    /// Returns the current schema version of the database.
+    pub fn schema_version(&self) -> Result<usize, RusticError> {
+        let version: usize = self.connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        Ok(version)
+    }
+
+    /// Applies every migration newer than the current schema version.
+    fn migrate(&self) -> Result<(), RusticError> {
+        let version = self.schema_version()?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = self.connection.unchecked_transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", index + 1)?;
+            transaction.commit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Imports the authors and the tasks from the CSV files written by
+    /// `CsvStorage`, skipping the authors and tasks that already exist.
+    /// Returns the number of imported authors and tasks.
+    pub fn import_csv(
+        &self,
+        authors_path: &Path,
+        tasks_path: &Path,
+    ) -> Result<(usize, usize), RusticError> {
+        let authors = CsvStorage::new(authors_path, tasks_path).load_authors()?;
+        let mut tasks = read_tasks_from_csv(tasks_path)?;
+        upgrade_task_owners(&mut tasks, &authors);
+
+        let transaction = self.connection.unchecked_transaction()?;
+        let mut author_count = 0;
+        let mut task_count = 0;
+
+        for author in &authors {
+            author_count += transaction.execute(
+                "INSERT OR IGNORE INTO authors (id, name, email, password, created_on)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    author.get_id().to_string(),
+                    author.get_name(),
+                    author.get_email(),
+                    author.get_password(),
+                    to_timestamp(author.get_created_on()),
+                ],
+            )?;
+        }
+
+        for task in &tasks {
+            if insert_task(&transaction, task)? {
+                task_count += 1;
+            }
+        }
+
+        transaction.commit()?;
+
+        Ok((author_count, task_count))
+    }
+
+    /// Loads the tasks of the author filtered by the status and the due date,
+    /// where only the tasks due strictly before `due_before` are kept.
+    pub fn query_tasks(
+        &self,
+        author: &Author,
+        status: Option<Status>,
+        due_before: Option<&DateTime<Local>>,
+    ) -> Result<Vec<Task>, RusticError> {
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {TASK_COLUMNS} FROM tasks
+            WHERE created_by = ?1
+                AND (?2 IS NULL OR status = ?2)
+                AND (?3 IS NULL OR due_date < ?3)
+            ORDER BY position"
+        ))?;
+
+        let rows = statement.query_map(
+            params![author.get_id().to_string(), status, due_before.map(to_timestamp)],
+            task_from_row,
+        )?;
+
+        self.with_task_links(rows.collect::<Result<Vec<TaskBuilder>, _>>()?)
+    }
+
+    /// Loads the changes made to the tasks of the author from `start` up to,
+    /// but not including, `end` along with the ids of their tasks,
+    /// from the oldest change.
+    pub fn query_events(
+        &self,
+        author: &Author,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+    ) -> Result<Vec<(Uuid, TaskEvent)>, RusticError> {
+        let mut statement = self.connection.prepare(
+            "SELECT task_events.task_id, changed_on, changed_by, field, old_value, new_value
+            FROM task_events JOIN tasks ON tasks.id = task_events.task_id
+            WHERE tasks.created_by = ?1 AND changed_on >= ?2 AND changed_on < ?3
+            ORDER BY changed_on, tasks.position, task_events.position"
+        )?;
+
+        let rows = statement.query_map(
+            params![author.get_id().to_string(), to_timestamp(start), to_timestamp(end)],
+            |row| Ok((uuid_column(row, 0)?, event_from_row(row, 1)?)),
+        )?;
+
+        Ok(rows.collect::<Result<Vec<(Uuid, TaskEvent)>, _>>()?)
+    }
+
+    /// Fills in the labels, the subtasks, the blockers and the history of the loaded tasks.
+    fn with_task_links(&self, mut tasks: Vec<TaskBuilder>) -> Result<Vec<Task>, RusticError> {
+        let mut labels = self.connection.prepare(
+            "SELECT label FROM task_labels WHERE task_id = ?1 ORDER BY position"
+        )?;
+        let mut subtasks = self.connection.prepare(
+            "SELECT subtask_id FROM task_subtasks WHERE task_id = ?1 ORDER BY position"
+        )?;
+        let mut blockers = self.connection.prepare(
+            "SELECT blocker_id FROM task_blockers WHERE task_id = ?1 ORDER BY position"
+        )?;
+        let mut events = self.connection.prepare(
+            "SELECT changed_on, changed_by, field, old_value, new_value
+            FROM task_events WHERE task_id = ?1 ORDER BY position"
+        )?;
+
+        for task in &mut tasks {
+            let id = task.as_task().get_id().to_string();
+            task.labels(labels
+                .query_map([&id], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?);
+            task.subtasks(subtasks
+                .query_map([&id], |row| uuid_column(row, 0))?
+                .collect::<Result<Vec<Uuid>, _>>()?);
+            task.blocked_by(blockers
+                .query_map([&id], |row| uuid_column(row, 0))?
+                .collect::<Result<Vec<Uuid>, _>>()?);
+            task.history(events
+                .query_map([&id], |row| event_from_row(row, 0))?
+                .collect::<Result<Vec<TaskEvent>, _>>()?);
+        }
+
+        Ok(tasks.into_iter().map(TaskBuilder::build).collect())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load_authors(&self) -> Result<Vec<Author>, RusticError> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, name, email, password, created_on FROM authors"
+        )?;
+
+        let authors = statement
+            .query_map([], author_from_row)?
+            .collect::<Result<Vec<Author>, _>>()?;
+
+        Ok(authors)
+    }
+
+    fn save_author(&self, author: &Author) -> Result<(), RusticError> {
+        let result = self.connection.execute(
+            "INSERT INTO authors (id, name, email, password, created_on)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                author.get_id().to_string(),
+                author.get_name(),
+                author.get_email(),
+                author.get_password(),
+                to_timestamp(author.get_created_on()),
+            ],
+        );
+
+        // The unique email column rejects the existing authors
+        match result {
+            Ok(_) => Ok(()),
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if e.code == ErrorCode::ConstraintViolation =>
+            {
+                Err(RusticError::AuthorExists)
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn find_author(&self, email: &str) -> Result<Option<Author>, RusticError> {
+        let author = self.connection
+            .query_row(
+                "SELECT id, name, email, password, created_on
+                FROM authors WHERE email = ?1",
+                [email],
+                author_from_row,
+            )
+            .optional()?;
+
+        Ok(author)
+    }
+
+    fn load_tasks(&self, author: &Author) -> Result<Vec<Task>, RusticError> {
+        self.query_tasks(author, None, None)
+    }
+
+    fn save_tasks(&self, author: &Author, tasks: &[Task]) -> Result<(), RusticError> {
+        let transaction = self.connection.unchecked_transaction()?;
+
+        // Replace the previously saved tasks of the author
+        transaction.execute("DELETE FROM tasks WHERE created_by = ?1", [author.get_id().to_string()])?;
+        for task in tasks {
+            insert_task(&transaction, task)?;
+        }
+
+        transaction.commit()?;
+
+        Ok(())
+    }
+}
+
+impl ToSql for Priority {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.value()))
+    }
+}
+
+impl FromSql for Priority {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        u32::column_result(value).map(Priority::set)
+    }
+}
+
+impl ToSql for Status {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let status = match self {
+            Status::Pending => "Pending",
+            Status::InProgress => "InProgress",
+            Status::Complete => "Complete",
+        };
+        Ok(ToSqlOutput::from(status))
+    }
+}
+
+impl FromSql for Status {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "Pending" => Ok(Status::Pending),
+            "InProgress" => Ok(Status::InProgress),
+            "Complete" => Ok(Status::Complete),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for TaskField {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for TaskField {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "name" => Ok(TaskField::Name),
+            "description" => Ok(TaskField::Description),
+            "due_date" => Ok(TaskField::DueDate),
+            "priority" => Ok(TaskField::Priority),
+            "status" => Ok(TaskField::Status),
+            "labels" => Ok(TaskField::Labels),
+            "completed_on" => Ok(TaskField::CompletedOn),
+            "recurrence" => Ok(TaskField::Recurrence),
+            "blocked_by" => Ok(TaskField::Blockers),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Inserts the task with its labels, subtasks, blockers and history, returning `false`
+/// if a task with the same id already exists.
+fn insert_task(connection: &Connection, task: &Task) -> Result<bool, RusticError> {
+    let inserted = connection.execute(
+        &format!(
+            "INSERT OR IGNORE INTO tasks (position, {TASK_COLUMNS})
+            VALUES (
+                (SELECT COUNT(*) FROM tasks WHERE created_by = ?9),
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
+            )"
+        ),
+        params![
+            task.get_id().to_string(),
+            task.get_name(),
+            task.get_description(),
+            to_timestamp(task.get_created_on()),
+            task.get_due_date().map(to_timestamp),
+            task.get_priority(),
+            task.get_status(),
+            task.get_completed_on().map(to_timestamp),
+            task.get_created_by(),
+            task.get_recurrence().map(Recurrence::to_string),
+            task.get_parent().map(Uuid::to_string),
+        ],
+    )? > 0;
+
+    if inserted {
+        let id = task.get_id().to_string();
+        for (position, label) in task.get_labels().iter().enumerate() {
+            connection.execute(
+                "INSERT INTO task_labels (task_id, position, label) VALUES (?1, ?2, ?3)",
+                params![id, position, label],
+            )?;
+        }
+
+        for (position, subtask_id) in task.get_subtask_ids().iter().enumerate() {
+            connection.execute(
+                "INSERT INTO task_subtasks (task_id, position, subtask_id) VALUES (?1, ?2, ?3)",
+                params![id, position, subtask_id.to_string()],
+            )?;
+        }
+
+        for (position, blocker_id) in task.get_blockers().iter().enumerate() {
+            connection.execute(
+                "INSERT INTO task_blockers (task_id, position, blocker_id) VALUES (?1, ?2, ?3)",
+                params![id, position, blocker_id.to_string()],
+            )?;
+        }
+
+        for (position, event) in task.get_history().iter().enumerate() {
+            connection.execute(
+                "INSERT INTO task_events
+                (task_id, position, changed_on, changed_by, field, old_value, new_value)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    position,
+                    to_timestamp(event.get_changed_on()),
+                    event.get_changed_by(),
+                    event.get_field(),
+                    event.get_old_value(),
+                    event.get_new_value(),
+                ],
+            )?;
+        }
+    }
+
+    Ok(inserted)
+}
+
+/// Builds an author from the selected columns.
+fn author_from_row(row: &Row) -> rusqlite::Result<Author> {
+    Ok(Author::from_saved(
+        uuid_column(row, 0)?,
+        &row.get::<_, String>(1)?,
+        &row.get::<_, String>(2)?,
+        &row.get::<_, String>(3)?,
+        timestamp_column(row, 4)?,
+    ))
+}
+
+/// Builds a task without its labels, subtasks, blockers and history from the `TASK_COLUMNS`.
+fn task_from_row(row: &Row) -> rusqlite::Result<TaskBuilder> {
+    let mut task = TaskBuilder::new(&row.get::<_, String>(8)?);
+    task.id(uuid_column(row, 0)?)
+        .name(&row.get::<_, String>(1)?)
+        .description(row.get(2)?)
+        .created_on(timestamp_column(row, 3)?)
+        .due_date(optional_timestamp_column(row, 4)?)
+        .priority(row.get(5)?)
+        .status(row.get(6)?)
+        .completed_on(optional_timestamp_column(row, 7)?)
+        .recurrence(recurrence_column(row, 9)?)
+        .parent(optional_uuid_column(row, 10)?);
+
+    Ok(task)
+}
+
+/// Builds a change of a task from the event columns starting at the index.
+fn event_from_row(row: &Row, index: usize) -> rusqlite::Result<TaskEvent> {
+    Ok(TaskEvent::new(
+        timestamp_column(row, index)?,
+        &row.get::<_, String>(index + 1)?,
+        row.get(index + 2)?,
+        row.get(index + 3)?,
+        row.get(index + 4)?,
+    ))
+}
+
+/// Formats the timestamp as a fixed-width UTC string, so
+/// that comparing the strings also compares the timestamps.
+///
+/// The timestamps keep every digit of `Local::now()`, so that the authors
+/// and the tasks read back are equal to the saved ones. The timestamps
+/// written to the microsecond by the first versions are padded by the
+/// version 6 migration, as they would sort after the longer ones.
+fn to_timestamp(date_time: &DateTime<Local>) -> String {
+    date_time
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+/// Reads a nullable repetition rule stored as text.
+fn recurrence_column(row: &Row, index: usize) -> rusqlite::Result<Option<Recurrence>> {
+    let value: Option<String> = row.get(index)?;
+    value.map(|rule| rule.parse::<Recurrence>()).transpose().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Reads a uuid stored as text.
+fn uuid_column(row: &Row, index: usize) -> rusqlite::Result<Uuid> {
+    let value: String = row.get(index)?;
+    Uuid::parse_str(&value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Reads a nullable uuid stored as text.
+fn optional_uuid_column(row: &Row, index: usize) -> rusqlite::Result<Option<Uuid>> {
+    match row.get_ref(index)? {
+        ValueRef::Null => Ok(None),
+        _ => uuid_column(row, index).map(Some),
+    }
+}
+
+/// Reads a timestamp written by `to_timestamp`.
+fn timestamp_column(row: &Row, index: usize) -> rusqlite::Result<DateTime<Local>> {
+    let value: String = row.get(index)?;
+    DateTime::parse_from_rfc3339(&value)
+        .map(|dt| dt.with_timezone(&Local))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+/// Reads a nullable timestamp written by `to_timestamp`.
+fn optional_timestamp_column(row: &Row, index: usize) -> rusqlite::Result<Option<DateTime<Local>>> {
+    match row.get_ref(index)? {
+        ValueRef::Null => Ok(None),
+        _ => timestamp_column(row, index).map(Some),
+    }
+}
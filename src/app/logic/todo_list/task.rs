@@ -3,14 +3,33 @@
 pub mod utils;
 use utils::*;
 
+/// The module that parses the due dates typed by hand.
+pub mod due_date;
+
+/// The module that handles the repetition of recurring tasks.
+pub mod recurrence;
+pub use recurrence::Recurrence;
+
+/// The module that records the changes made to the tasks.
+pub mod history;
+pub use history::{TaskEvent, TaskField};
+use history::{format_date, format_list};
+
+/// The module that builds the tasks read back from
+/// the storage backends and the imported files.
+mod builder;
+pub(crate) use builder::TaskBuilder;
+
+use std::fmt;
+
 use chrono::{DateTime, Local};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::{Author, RusticError};
 
 /// The attributes associated with the task.
-#[derive(Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Task {
     id: Uuid,
     name: String,
@@ -19,10 +38,35 @@ pub struct Task {
     due_date: Option<DateTime<Local>>,
     priority: Priority,
     status: Status,
-    #[serde(serialize_with = "serialize_labels")]
+    #[serde(
+        serialize_with = "serialize_labels",
+        deserialize_with = "deserialize_labels"
+    )]
     labels: Vec<String>,
     completed_on: Option<DateTime<Local>>,
     created_by: String,
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
+    #[serde(default)]
+    parent: Option<Uuid>,
+    #[serde(
+        default,
+        serialize_with = "serialize_ids",
+        deserialize_with = "deserialize_ids"
+    )]
+    subtasks: Vec<Uuid>,
+    #[serde(
+        default,
+        serialize_with = "serialize_ids",
+        deserialize_with = "deserialize_ids"
+    )]
+    blocked_by: Vec<Uuid>,
+    #[serde(
+        default,
+        serialize_with = "serialize_history",
+        deserialize_with = "deserialize_history"
+    )]
+    history: Vec<TaskEvent>,
 }
 
 impl Task {
@@ -45,7 +89,12 @@ impl Task {
             status: Status::Pending,
             labels: labels.iter().map(|&s| s.to_string()).collect(),
             completed_on: None,
-            created_by: author.get_name().to_string(),
+            created_by: author.get_id().to_string(),
+            recurrence: None,
+            parent: None,
+            subtasks: Vec::new(),
+            blocked_by: Vec::new(),
+            history: Vec::new(),
         })
     }
 
@@ -94,14 +143,144 @@ impl Task {
         self.completed_on.as_ref()
     }
 
-    /// Returns an immutable borrow to the `created_by` field.
+    /// Returns an immutable borrow to the `created_by` field,
+    /// which holds the id of the author.
     pub fn get_created_by(&self) -> &str {
         &self.created_by[..]
     }
 
+    /// Checks if the task was created by the author, going by the id
+    /// of the author since the names of the authors are not unique.
+    pub fn is_created_by(&self, author: &Author) -> bool {
+        self.created_by == author.get_id().to_string()
+    }
+
+    /// Returns an immutable borrow to the `recurrence` field.
+    pub fn get_recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    /// Returns an immutable borrow to the `parent` field.
+    pub fn get_parent(&self) -> Option<&Uuid> {
+        self.parent.as_ref()
+    }
+
+    /// Returns an immutable borrow to the `subtasks` field.
+    pub fn get_subtask_ids(&self) -> &Vec<Uuid> {
+        &self.subtasks
+    }
+
+    /// Returns an immutable borrow to the `blocked_by` field.
+    pub fn get_blockers(&self) -> &Vec<Uuid> {
+        &self.blocked_by
+    }
+
+    /// Returns an immutable borrow to the `history` field.
+    pub fn get_history(&self) -> &Vec<TaskEvent> {
+        &self.history
+    }
+
+    /// Returns the changes made to the task from `start` up to,
+    /// but not including, `end` in the order they were made.
+    pub fn get_changes_between(
+        &self,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+    ) -> Vec<&TaskEvent> {
+        self.history.iter().filter(|event| event.is_between(start, end)).collect()
+    }
+
+    /// Adds the change to the history, unless the value stayed the same.
+    /// The change is made by the author of the task, who is the only one
+    /// that `ToDoList` lets edit it.
+    fn record_change(
+        &mut self,
+        field: TaskField,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) {
+        if old_value == new_value {
+            return;
+        }
+
+        self.history.push(TaskEvent::new(
+            Local::now(),
+            &self.created_by,
+            field,
+            old_value,
+            new_value,
+        ));
+    }
+
+    /// Sets the parent of the task, where `ToDoList` keeps
+    /// the subtasks of the parent in step with it.
+    pub(crate) fn set_parent(&mut self, parent: Option<Uuid>) {
+        self.parent = parent;
+    }
+
+    /// Inserts the subtask at the index, where the indices
+    /// past the last subtask add it to the end.
+    pub(crate) fn insert_subtask(&mut self, index: usize, id: Uuid) {
+        let index = index.min(self.subtasks.len());
+        self.subtasks.insert(index, id);
+    }
+
+    /// Removes the subtask, returning the index it had.
+    pub(crate) fn remove_subtask(&mut self, id: &Uuid) -> Option<usize> {
+        let index = self.subtasks.iter().position(|subtask_id| subtask_id == id)?;
+        self.subtasks.remove(index);
+
+        Some(index)
+    }
+
+    /// Adds the blocker to the task.
+    pub(crate) fn add_blocker(&mut self, id: Uuid) {
+        let old_value = format_list(&self.blocked_by);
+        self.blocked_by.push(id);
+        self.record_change(TaskField::Blockers, old_value, format_list(&self.blocked_by));
+    }
+
+    /// Removes the blockers from the task, returning
+    /// whether it was blocked by any of them.
+    pub(crate) fn remove_blockers(&mut self, ids: &[Uuid]) -> bool {
+        let old_value = format_list(&self.blocked_by);
+        let count = self.blocked_by.len();
+        self.blocked_by.retain(|blocker_id| !ids.contains(blocker_id));
+        self.record_change(TaskField::Blockers, old_value, format_list(&self.blocked_by));
+
+        self.blocked_by.len() < count
+    }
+
+    /// Checks if the task has the given label.
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l == label)
+    }
+
+    /// Renames the task.
+    pub fn rename(&mut self, name: &str) {
+        let old_value = Some(self.name.clone());
+        self.name = name.to_string();
+        self.record_change(TaskField::Name, old_value, Some(self.name.clone()));
+    }
+
+    /// Sets the due date of the task.
+    pub fn set_due_date(&mut self, due_date: Option<DateTime<Local>>) {
+        let old_value = format_date(self.due_date.as_ref());
+        self.due_date = due_date;
+        let new_value = format_date(self.due_date.as_ref());
+        self.record_change(TaskField::DueDate, old_value, new_value);
+    }
+
     /// Updates the description of the task.
     pub fn update_description(&mut self, desc: &str) {
-        self.description = Some(desc.to_string());
+        self.set_description(Some(desc));
+    }
+
+    /// Sets the description of the task, or clears it.
+    pub(crate) fn set_description(&mut self, desc: Option<&str>) {
+        let old_value = self.description.clone();
+        self.description = desc.map(|desc| desc.to_string());
+        self.record_change(TaskField::Description, old_value, self.description.clone());
     }
 
     /// Changes the priority level of the task: \[
@@ -110,27 +289,82 @@ impl Task {
     ///     High = 3
     /// \]
     pub fn change_priority(&mut self, priority: u32) {
+        let old_value = Some(self.priority.to_string());
         self.priority = Priority::set(priority);
+        self.record_change(TaskField::Priority, old_value, Some(self.priority.to_string()));
     }
 
-    /// Updates the status of the task.
+    /// Updates the status of the task. Use `ToDoList::update_status`
+    /// to check the blockers and roll the status up to the parent.
     pub fn update_status(&mut self, status: Status) {
+        let old_value = Some(self.status.to_string());
         self.status = status;
+        self.record_change(TaskField::Status, old_value, Some(self.status.to_string()));
     }
 
     /// Updates the labels associated with the task.
     pub fn update_labels(&mut self, labels: Vec<&str>) {
+        let old_value = format_list(&self.labels);
         self.labels = labels.iter().map(|&s| s.to_string()).collect();
+        self.record_change(TaskField::Labels, old_value, format_list(&self.labels));
+    }
+
+    /// Sets how the task repeats, or makes it a one-off task.
+    pub fn set_recurrence(&mut self, recurrence: Option<Recurrence>) {
+        let old_value = self.recurrence.as_ref().map(Recurrence::to_string);
+        self.recurrence = recurrence;
+        let new_value = self.recurrence.as_ref().map(Recurrence::to_string);
+        self.record_change(TaskField::Recurrence, old_value, new_value);
     }
 
     /// Sets the `completed_on` field of the task to the current time.
-    pub fn mark_completed(&mut self) {
-        self.completed_on = Some(Local::now());
+    /// Returns the next occurrence if the task is recurring, which
+    /// starts with an empty history, unless its due date is past
+    /// the last date that can be represented. Does nothing if the
+    /// task has already been completed.
+    pub fn mark_completed(&mut self) -> Option<Task> {
+        if self.completed_on.is_some() {
+            return None;
+        }
+
+        let completed_on = Local::now();
+        let old_value = format_date(self.completed_on.as_ref());
+        self.completed_on = Some(completed_on);
+        let new_value = format_date(Some(&completed_on));
+        self.record_change(TaskField::CompletedOn, old_value, new_value);
+
+        let recurrence = self.recurrence.as_ref()?;
+        let due_date = recurrence.next_due_date(self.due_date.as_ref(), &completed_on)?;
+
+        Some(Task {
+            id: Uuid::new_v4(),
+            created_on: completed_on,
+            due_date: Some(due_date),
+            status: Status::Pending,
+            completed_on: None,
+            subtasks: Vec::new(),
+            history: Vec::new(),
+            ..self.clone()
+        })
+    }
+
+    /// Sets the task back to pending and clears the `completed_on` field.
+    pub fn reopen(&mut self) {
+        self.update_status(Status::Pending);
+        self.set_completed_on(None);
+    }
+
+    /// Sets the `completed_on` field, such as when undoing a change to it.
+    pub(crate) fn set_completed_on(&mut self, completed_on: Option<DateTime<Local>>) {
+        let old_value = format_date(self.completed_on.as_ref());
+        self.completed_on = completed_on;
+        let new_value = format_date(self.completed_on.as_ref());
+        self.record_change(TaskField::CompletedOn, old_value, new_value);
     }
 }
 
-/// The task priority level.
-#[derive(Debug, Default, Serialize)]
+/// The task priority level, ordered from low to high.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum Priority {
     Low,
     #[default]
@@ -152,13 +386,57 @@ impl Priority {
             _ => Priority::Medium,
         };
     }
+
+    /// Returns the value of the priority: \[
+    ///     Low = 1,
+    ///     Medium = 2,
+    ///     High = 3
+    /// \]
+    pub fn value(&self) -> u32 {
+        match self {
+            Priority::Low => 1,
+            Priority::Medium => 2,
+            Priority::High => 3,
+        }
+    }
+
+    /// Returns the priority from its name or its value,
+    /// such as `high` or `3`, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "low" | "1" => Some(Priority::Low),
+            "medium" | "2" => Some(Priority::Medium),
+            "high" | "3" => Some(Priority::High),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Priority::Low => write!(f, "Low"),
+            Priority::Medium => write!(f, "Medium"),
+            Priority::High => write!(f, "High"),
+        }
+    }
 }
 
-/// The task completion status.
-#[derive(Debug, Default, Serialize)]
+/// The task completion status, ordered from pending to complete.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum Status {
     #[default]
     Pending,
     InProgress,
     Complete,
 }
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pending => write!(f, "Pending"),
+            Status::InProgress => write!(f, "In Progress"),
+            Status::Complete => write!(f, "Complete"),
+        }
+    }
+}
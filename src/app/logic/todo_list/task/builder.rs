@@ -0,0 +1,160 @@
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+
+use super::{Priority, Recurrence, Status, Task, TaskEvent};
+
+/// The builder of the tasks that are read back from the storage backends
+/// and the imported files, which carry the fields that `Task::new` fills
+/// in by itself, such as the id, the status and the links.
+#[derive(Clone, Debug)]
+pub(crate) struct TaskBuilder {
+    task: Task,
+}
+
+impl TaskBuilder {
+    /// Starts a pending task with a new id, created now by the author.
+    pub(crate) fn new(created_by: &str) -> Self {
+        TaskBuilder {
+            task: Task {
+                id: Uuid::new_v4(),
+                created_on: Local::now(),
+                created_by: created_by.to_string(),
+                ..Task::default()
+            },
+        }
+    }
+
+    /// Returns an immutable borrow to the task built so far.
+    pub(crate) fn as_task(&self) -> &Task {
+        &self.task
+    }
+
+    /// Sets the `id` field.
+    pub(crate) fn id(&mut self, id: Uuid) -> &mut Self {
+        self.task.id = id;
+        self
+    }
+
+    /// Sets the `name` field.
+    pub(crate) fn name(&mut self, name: &str) -> &mut Self {
+        self.task.name = name.to_string();
+        self
+    }
+
+    /// Sets the `description` field.
+    pub(crate) fn description(&mut self, description: Option<String>) -> &mut Self {
+        self.task.description = description;
+        self
+    }
+
+    /// Sets the `created_on` field.
+    pub(crate) fn created_on(&mut self, created_on: DateTime<Local>) -> &mut Self {
+        self.task.created_on = created_on;
+        self
+    }
+
+    /// Sets the `due_date` field.
+    pub(crate) fn due_date(&mut self, due_date: Option<DateTime<Local>>) -> &mut Self {
+        self.task.due_date = due_date;
+        self
+    }
+
+    /// Sets the `priority` field.
+    pub(crate) fn priority(&mut self, priority: Priority) -> &mut Self {
+        self.task.priority = priority;
+        self
+    }
+
+    /// Sets the `status` field.
+    pub(crate) fn status(&mut self, status: Status) -> &mut Self {
+        self.task.status = status;
+        self
+    }
+
+    /// Adds the label after the others.
+    pub(crate) fn label(&mut self, label: String) -> &mut Self {
+        self.task.labels.push(label);
+        self
+    }
+
+    /// Sets the `labels` field.
+    pub(crate) fn labels(&mut self, labels: Vec<String>) -> &mut Self {
+        self.task.labels = labels;
+        self
+    }
+
+    /// Sets the `completed_on` field.
+    pub(crate) fn completed_on(&mut self, completed_on: Option<DateTime<Local>>) -> &mut Self {
+        self.task.completed_on = completed_on;
+        self
+    }
+
+    /// Sets the `created_by` field.
+    pub(crate) fn created_by(&mut self, created_by: &str) -> &mut Self {
+        self.task.created_by = created_by.to_string();
+        self
+    }
+
+    /// Sets the `recurrence` field.
+    pub(crate) fn recurrence(&mut self, recurrence: Option<Recurrence>) -> &mut Self {
+        self.task.recurrence = recurrence;
+        self
+    }
+
+    /// Sets the `parent` field.
+    pub(crate) fn parent(&mut self, parent: Option<Uuid>) -> &mut Self {
+        self.task.parent = parent;
+        self
+    }
+
+    /// Adds the subtask after the others.
+    pub(crate) fn subtask(&mut self, id: Uuid) -> &mut Self {
+        self.task.subtasks.push(id);
+        self
+    }
+
+    /// Sets the `subtasks` field.
+    pub(crate) fn subtasks(&mut self, subtasks: Vec<Uuid>) -> &mut Self {
+        self.task.subtasks = subtasks;
+        self
+    }
+
+    /// Adds the blocker after the others.
+    pub(crate) fn blocker(&mut self, id: Uuid) -> &mut Self {
+        self.task.blocked_by.push(id);
+        self
+    }
+
+    /// Sets the `blocked_by` field.
+    pub(crate) fn blocked_by(&mut self, blocked_by: Vec<Uuid>) -> &mut Self {
+        self.task.blocked_by = blocked_by;
+        self
+    }
+
+    /// Sets the `history` field.
+    pub(crate) fn history(&mut self, history: Vec<TaskEvent>) -> &mut Self {
+        self.task.history = history;
+        self
+    }
+
+    /// Returns the task, leaving out the links from the task to itself,
+    /// which `ToDoList` never makes either.
+    pub(crate) fn build(self) -> Task {
+        let mut task = self.task;
+        let id = task.id;
+
+        if task.parent == Some(id) {
+            task.parent = None;
+        }
+        task.subtasks.retain(|subtask_id| subtask_id != &id);
+        task.blocked_by.retain(|blocker_id| blocker_id != &id);
+
+        task
+    }
+}
+
+impl From<Task> for TaskBuilder {
+    fn from(task: Task) -> Self {
+        TaskBuilder { task }
+    }
+}
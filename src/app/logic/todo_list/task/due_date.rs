@@ -0,0 +1,221 @@
+use chrono::{
+    DateTime, Datelike, Days, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Weekday,
+};
+
+use crate::RusticError;
+
+/// The formats of the local date and times without an offset.
+const LOCAL_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Parses the due date typed by hand, resolving the relative phrases
+/// against `now`. The accepted inputs are:
+/// - timestamps such as `2024-06-10T16:00:00+05:30`
+/// - local date and times such as `2024-06-10T16:00` or `2024-06-10 16:00`
+/// - plain dates such as `2024-06-10`, which are due at the end of the day
+/// - the days `today`, `tomorrow`, `friday` or `next friday`, where
+///   a weekday is always the first one after today
+/// - the periods `next week`, `next month`, `in 3 days` or `in 2 hours`
+/// - the ends of periods `end of week`, `end of month` or `end of year`
+///
+/// The dates can be followed by a time such as `5pm`, `5:30pm`, `17:30`
+/// or `noon`, optionally after `at`, and a time alone is due today.
+pub fn parse_natural_date(
+    input: &str,
+    now: &DateTime<Local>,
+) -> Result<DateTime<Local>, RusticError> {
+    let input = input.trim();
+    let invalid = |reason: &str| RusticError::InvalidDueDate(format!(
+        "'{input}' {reason}. Try a date such as 2024-06-10, a time such as 17:30 \
+        or a phrase such as \"tomorrow 5pm\", \"next friday\" or \"in 3 days\"."
+    ));
+
+    // Timestamps with an offset are taken as they are
+    if let Ok(due_date) = DateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S%z") {
+        return Ok(due_date.with_timezone(&Local));
+    }
+    if let Ok(due_date) = DateTime::parse_from_rfc3339(input) {
+        return Ok(due_date.with_timezone(&Local));
+    }
+    for format in LOCAL_FORMATS {
+        if let Ok(due_date) = NaiveDateTime::parse_from_str(input, format) {
+            return to_local(due_date)
+                .ok_or_else(|| invalid("does not exist in the local time zone"));
+        }
+    }
+
+    let lowercase = input.to_lowercase();
+    let mut words: Vec<&str> = lowercase
+        .split_whitespace()
+        .filter(|&word| word != "at" && word != "the")
+        .collect();
+    if words.is_empty() {
+        return Err(invalid("is empty"));
+    }
+
+    // Split the time of day from the end of the phrase
+    let time = match words.last().and_then(|word| parse_time(word)) {
+        Some(time) => {
+            words.pop();
+            Some(time)
+        },
+        None => None,
+    };
+
+    let today = now.date_naive();
+    let out_of_range = || invalid("is out of range");
+
+    let weekday = match words[..] {
+        [word] | ["next", word] | ["this", word] => parse_weekday(word),
+        _ => None,
+    };
+
+    let date = if let Some(weekday) = weekday {
+        next_weekday(today, weekday).ok_or_else(out_of_range)?
+    } else {
+        match words[..] {
+            [] if time.is_some() => today,
+            ["today"] => today,
+            ["tomorrow"] => add_days(today, 1).ok_or_else(out_of_range)?,
+            ["yesterday"] => today.checked_sub_days(Days::new(1)).ok_or_else(out_of_range)?,
+            ["next", "week"] => add_days(today, 7).ok_or_else(out_of_range)?,
+            ["next", "month"] => add_months(today, 1).ok_or_else(out_of_range)?,
+            ["next", "year"] => add_months(today, 12).ok_or_else(out_of_range)?,
+            ["end", "of", period] => {
+                end_of(today, period).ok_or_else(|| invalid("is not the end of a known period"))?
+            },
+            ["in", amount, unit] => {
+                let amount = match amount {
+                    "a" | "an" | "one" => 1,
+                    amount => amount
+                        .parse::<u32>()
+                        .map_err(|_| invalid("does not have a valid amount"))?,
+                };
+
+                match unit.trim_end_matches('s') {
+                    // The hours and minutes are exact, so they cannot have a time of day
+                    "minute" | "min" | "hour" | "hr" if time.is_some() => {
+                        return Err(invalid("cannot have both an amount of hours and a time"));
+                    },
+                    "minute" | "min" => {
+                        return now
+                            .checked_add_signed(Duration::minutes(i64::from(amount)))
+                            .ok_or_else(out_of_range);
+                    },
+                    "hour" | "hr" => {
+                        return now
+                            .checked_add_signed(Duration::hours(i64::from(amount)))
+                            .ok_or_else(out_of_range);
+                    },
+                    "day" => add_days(today, u64::from(amount)).ok_or_else(out_of_range)?,
+                    "week" => add_days(today, 7 * u64::from(amount)).ok_or_else(out_of_range)?,
+                    "month" => add_months(today, amount).ok_or_else(out_of_range)?,
+                    "year" => amount
+                        .checked_mul(12)
+                        .and_then(|months| add_months(today, months))
+                        .ok_or_else(out_of_range)?,
+                    _ => return Err(invalid("does not have a known unit of time")),
+                }
+            },
+            [date] => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| invalid("is not a date"))?,
+            _ => return Err(invalid("is not a date")),
+        }
+    };
+
+    // The dates without a time are due at the end of the day
+    let time = time.unwrap_or_else(end_of_day);
+    to_local(date.and_time(time))
+        .ok_or_else(|| invalid("does not exist in the local time zone"))
+}
+
+/// Parses the time of day such as `5pm`, `5:30pm`, `17:30` or `noon`.
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    if word == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+
+    let (clock, offset) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (word, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // A bare number is only a time with am or pm
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parses the full or the short name of the weekday.
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Returns the first date after `date` that falls on the weekday.
+fn next_weekday(date: NaiveDate, weekday: Weekday) -> Option<NaiveDate> {
+    let days = (7 + weekday.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+    let days = if days == 0 { 7 } else { days };
+
+    add_days(date, u64::from(days))
+}
+
+/// Adds the days to the date.
+fn add_days(date: NaiveDate, days: u64) -> Option<NaiveDate> {
+    date.checked_add_days(Days::new(days))
+}
+
+/// Adds the months to the date, clamping the day to the end of the month.
+fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(months))
+}
+
+/// Returns the last day of the week, the month or the year of the date.
+fn end_of(date: NaiveDate, period: &str) -> Option<NaiveDate> {
+    match period {
+        "week" => add_days(date, u64::from(6 - date.weekday().num_days_from_monday())),
+        "month" => date
+            .with_day(1)?
+            .checked_add_months(Months::new(1))?
+            .pred_opt(),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 12, 31),
+        _ => None,
+    }
+}
+
+/// Returns the last second of the day.
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default()
+}
+
+/// Resolves the local date and time, picking the earlier one when the
+/// clocks go back and nothing when they skip over it.
+fn to_local(date_time: NaiveDateTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&date_time).earliest()
+}
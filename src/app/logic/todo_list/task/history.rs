@@ -0,0 +1,128 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// The field of a task that a change was made to.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum TaskField {
+    Name,
+    Description,
+    DueDate,
+    Priority,
+    Status,
+    Labels,
+    CompletedOn,
+    Recurrence,
+    Blockers,
+}
+
+impl fmt::Display for TaskField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskField::Name => write!(f, "name"),
+            TaskField::Description => write!(f, "description"),
+            TaskField::DueDate => write!(f, "due_date"),
+            TaskField::Priority => write!(f, "priority"),
+            TaskField::Status => write!(f, "status"),
+            TaskField::Labels => write!(f, "labels"),
+            TaskField::CompletedOn => write!(f, "completed_on"),
+            TaskField::Recurrence => write!(f, "recurrence"),
+            TaskField::Blockers => write!(f, "blocked_by"),
+        }
+    }
+}
+
+/// A change made to a field of a task, along with who made it and when,
+/// where the values are written as text and `None` means unset.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TaskEvent {
+    changed_on: DateTime<Local>,
+    changed_by: String,
+    field: TaskField,
+    old_value: Option<String>,
+    new_value: Option<String>,
+}
+
+impl TaskEvent {
+    /// Creates the change made by the author, such as
+    /// one read back from the storage backends.
+    pub(crate) fn new(
+        changed_on: DateTime<Local>,
+        changed_by: &str,
+        field: TaskField,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        TaskEvent {
+            changed_on,
+            changed_by: changed_by.to_string(),
+            field,
+            old_value,
+            new_value,
+        }
+    }
+
+    /// Returns the same change, made by the author with the given id instead.
+    pub(crate) fn changed_by(&self, changed_by: &str) -> Self {
+        TaskEvent { changed_by: changed_by.to_string(), ..self.clone() }
+    }
+
+    /// Returns an immutable borrow to the `changed_on` field.
+    pub fn get_changed_on(&self) -> &DateTime<Local> {
+        &self.changed_on
+    }
+
+    /// Returns an immutable borrow to the `changed_by` field.
+    pub fn get_changed_by(&self) -> &str {
+        &self.changed_by[..]
+    }
+
+    /// Returns an immutable borrow to the `field` field.
+    pub fn get_field(&self) -> &TaskField {
+        &self.field
+    }
+
+    /// Returns an immutable borrow to the `old_value` field.
+    pub fn get_old_value(&self) -> Option<&str> {
+        self.old_value.as_deref()
+    }
+
+    /// Returns an immutable borrow to the `new_value` field.
+    pub fn get_new_value(&self) -> Option<&str> {
+        self.new_value.as_deref()
+    }
+
+    /// Checks if the change was made from `start` up to, but not including, `end`.
+    pub fn is_between(&self, start: &DateTime<Local>, end: &DateTime<Local>) -> bool {
+        start <= &self.changed_on && &self.changed_on < end
+    }
+}
+
+impl fmt::Display for TaskEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} changed {} from {} to {}",
+            self.changed_on.format("%Y-%m-%d %H:%M:%S"),
+            self.changed_by,
+            self.field,
+            self.old_value.as_deref().unwrap_or("nothing"),
+            self.new_value.as_deref().unwrap_or("nothing"),
+        )
+    }
+}
+
+/// Formats the date of a change as RFC 3339 text.
+pub(crate) fn format_date(date_time: Option<&DateTime<Local>>) -> Option<String> {
+    date_time.map(DateTime::to_rfc3339)
+}
+
+/// Formats the list of a change as comma-separated text, where the empty list is unset.
+pub(crate) fn format_list<T: ToString>(items: &[T]) -> Option<String> {
+    if items.is_empty() {
+        return None;
+    }
+
+    Some(items.iter().map(T::to_string).collect::<Vec<String>>().join(","))
+}
@@ -0,0 +1,241 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::RusticError;
+
+/// The longest interval accepted when parsing a rule, which keeps
+/// the next occurrences of the imported rules within reach.
+pub const MAX_INTERVAL: u32 = 1000;
+
+/// The repetition rule of a recurring task, which
+/// is written as a subset of the iCalendar RRULE.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Recurrence {
+    /// Repeats every `interval` days: `FREQ=DAILY;INTERVAL=2`
+    Daily { interval: u32 },
+    /// Repeats every `interval` weeks on the weekdays: `FREQ=WEEKLY;BYDAY=MO,WE`
+    Weekly { interval: u32, weekdays: Vec<Weekday> },
+    /// Repeats every `interval` months on the day: `FREQ=MONTHLY;BYMONTHDAY=15`
+    Monthly { interval: u32, day: u32 },
+    /// Repeats `days` days after the completion: `FREQ=DAILY;INTERVAL=3;X-FROM=COMPLETION`
+    AfterCompletion { days: u32 },
+}
+
+impl Recurrence {
+    /// Returns the due date of the next occurrence of a task that was due
+    /// on `due_date` and completed on `completed_on`. The occurrences that
+    /// fall before the completion are skipped, so that a late completion
+    /// does not leave the next occurrence overdue.
+    ///
+    /// Returns `None` when the next occurrence is past the last date that
+    /// can be represented, which ends the repetition.
+    pub fn next_due_date(
+        &self,
+        due_date: Option<&DateTime<Local>>,
+        completed_on: &DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        if let Recurrence::AfterCompletion { days } = self {
+            return completed_on.checked_add_days(Days::new(u64::from(*days).max(1)));
+        }
+
+        let mut next = self.step(due_date.unwrap_or(completed_on))?;
+        while next <= *completed_on {
+            next = self.step(&next)?;
+        }
+
+        Some(next)
+    }
+
+    /// Returns the first occurrence after the date.
+    fn step(&self, date: &DateTime<Local>) -> Option<DateTime<Local>> {
+        let today = date.date_naive();
+        let next_date = match self {
+            Recurrence::Daily { interval } => {
+                today.checked_add_days(Days::new(u64::from(*interval).max(1)))?
+            },
+            Recurrence::AfterCompletion { days } => {
+                today.checked_add_days(Days::new(u64::from(*days).max(1)))?
+            },
+            Recurrence::Weekly { interval, weekdays } => {
+                next_weekday(today, *interval, weekdays)?
+            },
+            Recurrence::Monthly { interval, day } => {
+                next_month_day(today, *interval, *day)?
+            },
+        };
+
+        at_date(date, next_date)
+    }
+}
+
+/// Returns the next date on one of the weekdays, moving on to the
+/// week `interval` weeks later once the current week is over.
+fn next_weekday(today: NaiveDate, interval: u32, weekdays: &[Weekday]) -> Option<NaiveDate> {
+    let interval = u64::from(interval).max(1);
+    if weekdays.is_empty() {
+        return today.checked_add_days(Days::new(7 * interval));
+    }
+
+    // Look for a later weekday in the current week
+    let current = today.weekday().num_days_from_monday();
+    let later = weekdays
+        .iter()
+        .map(|weekday| weekday.num_days_from_monday())
+        .filter(|&offset| offset > current)
+        .min();
+    if let Some(offset) = later {
+        return today.checked_add_days(Days::new(u64::from(offset - current)));
+    }
+
+    // Otherwise start from the first weekday of a later week
+    let first = weekdays
+        .iter()
+        .map(|weekday| weekday.num_days_from_monday())
+        .min()
+        .unwrap_or_default();
+    let week_start = today.checked_sub_days(Days::new(u64::from(current)))?;
+
+    week_start.checked_add_days(Days::new(7 * interval + u64::from(first)))
+}
+
+/// Returns the day `interval` months later, clamped
+/// to the last day of the month if it is too short.
+fn next_month_day(today: NaiveDate, interval: u32, day: u32) -> Option<NaiveDate> {
+    let month_start = today.with_day(1)?.checked_add_months(Months::new(interval.max(1)))?;
+    let last_day = match month_start.checked_add_months(Months::new(1)) {
+        Some(next_month_start) => next_month_start.pred_opt()?.day(),
+        // The last month that can be represented
+        None => 31,
+    };
+
+    month_start.with_day(day.clamp(1, last_day))
+}
+
+/// Moves the date-time to another date, keeping the time of the day.
+fn at_date(date_time: &DateTime<Local>, date: NaiveDate) -> Option<DateTime<Local>> {
+    date.and_time(date_time.time())
+        .and_local_timezone(Local)
+        .earliest()
+        .or_else(|| date_time.checked_add_signed(date - date_time.date_naive()))
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Daily { interval } => {
+                write!(f, "FREQ=DAILY;INTERVAL={interval}")
+            },
+            Recurrence::Weekly { interval, weekdays } if weekdays.is_empty() => {
+                write!(f, "FREQ=WEEKLY;INTERVAL={interval}")
+            },
+            Recurrence::Weekly { interval, weekdays } => {
+                let weekdays: Vec<&str> = weekdays.iter().map(|&w| weekday_code(w)).collect();
+                write!(f, "FREQ=WEEKLY;INTERVAL={interval};BYDAY={}", weekdays.join(","))
+            },
+            Recurrence::Monthly { interval, day } => {
+                write!(f, "FREQ=MONTHLY;INTERVAL={interval};BYMONTHDAY={day}")
+            },
+            Recurrence::AfterCompletion { days } => {
+                write!(f, "FREQ=DAILY;INTERVAL={days};X-FROM=COMPLETION")
+            },
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RusticError;
+
+    /// Parses the subset of the iCalendar RRULE written by `Display`.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let invalid = || RusticError::InvalidRecurrence(rule.to_string());
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut weekdays = Vec::new();
+        let mut month_day = None;
+        let mut after_completion = false;
+
+        let rule_body = rule.trim().trim_start_matches("RRULE:");
+        for part in rule_body.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(invalid)?;
+            match &key.to_ascii_uppercase()[..] {
+                "FREQ" => frequency = Some(value.to_ascii_uppercase()),
+                "INTERVAL" => interval = value.parse().map_err(|_| invalid())?,
+                "BYDAY" => {
+                    // An empty value repeats on the weekday of the due date
+                    weekdays = value
+                        .split(',')
+                        .filter(|code| !code.is_empty())
+                        .map(|code| parse_weekday(code).ok_or_else(invalid))
+                        .collect::<Result<_, _>>()?;
+                },
+                "BYMONTHDAY" => month_day = Some(value.parse().map_err(|_| invalid())?),
+                "X-FROM" if value.eq_ignore_ascii_case("COMPLETION") => after_completion = true,
+                _ => return Err(invalid()),
+            }
+        }
+
+        if interval == 0 || interval > MAX_INTERVAL {
+            return Err(invalid());
+        }
+
+        match frequency.as_deref() {
+            Some("DAILY") if after_completion => Ok(Recurrence::AfterCompletion { days: interval }),
+            Some("DAILY") => Ok(Recurrence::Daily { interval }),
+            Some("WEEKLY") => Ok(Recurrence::Weekly { interval, weekdays }),
+            Some("MONTHLY") => match month_day {
+                Some(day @ 1..=31) => Ok(Recurrence::Monthly { interval, day }),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rule = String::deserialize(deserializer)?;
+        rule.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Returns the two-letter iCalendar code of the weekday.
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Parses the two-letter iCalendar code of the weekday.
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    match &code.trim().to_ascii_uppercase()[..] {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
@@ -1,6 +1,11 @@
+use std::ops::Range;
+
 use chrono::{DateTime, Local};
-use serde::Serializer;
+use serde::{Deserialize, Deserializer, Serializer};
+use uuid::Uuid;
 
+use super::due_date::parse_natural_date;
+use super::{Author, Priority, Task, TaskEvent};
 use crate::error::RusticError;
 
 /// Serialises the labels field into a comma-separated string.
@@ -15,11 +20,213 @@ where
     serializer.serialize_str(&labels_str)
 }
 
+/// Deserialises the comma-separated labels string into a vector.
+pub fn deserialize_labels<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let labels_str = String::deserialize(deserializer)?;
+    let labels = labels_str
+        .split(',')
+        .map(|label| label.trim())
+        .filter(|label| !label.is_empty())
+        .map(|label| label.to_string())
+        .collect();
+    Ok(labels)
+}
+
+/// Serialises the task ids into a comma-separated string.
+pub fn serialize_ids<S>(
+    ids: &[Uuid],
+    serializer: S
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    serializer.serialize_str(&ids.join(","))
+}
+
+/// Deserialises the comma-separated task ids string into a vector.
+pub fn deserialize_ids<'de, D>(deserializer: D) -> Result<Vec<Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ids_str = String::deserialize(deserializer)?;
+    ids_str
+        .split(',')
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .map(|id| Uuid::parse_str(id).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+/// Serialises the history of the task into a JSON string,
+/// where the empty history is an empty string.
+pub fn serialize_history<S>(
+    history: &[TaskEvent],
+    serializer: S
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if history.is_empty() {
+        return serializer.serialize_str("");
+    }
+
+    let history_str = serde_json::to_string(history).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(&history_str)
+}
+
+/// Deserialises the JSON history string into a vector.
+pub fn deserialize_history<'de, D>(deserializer: D) -> Result<Vec<TaskEvent>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let history_str = String::deserialize(deserializer)?;
+    if history_str.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    serde_json::from_str(&history_str).map_err(serde::de::Error::custom)
+}
+
 /// Parses the due date from the input string.
 pub fn parse_due_date(date_str: &str) -> Result<DateTime<Local>, RusticError> {
-    let due_date: DateTime<Local> = DateTime::parse_from_str(
-        date_str,
-        "%Y-%m-%dT%H:%M:%S%z"
-    ).map(|dt| dt.with_timezone(&Local))?;
-    Ok(due_date)
+    parse_natural_date(date_str, &Local::now())
+}
+
+/// The details of a task written in the quick-add syntax, such as
+/// `Write paper #Thesis !high due:friday @desc "outline first"`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QuickAdd {
+    pub name: String,
+    pub description: Option<String>,
+    pub due_date: Option<DateTime<Local>>,
+    pub priority: Option<Priority>,
+    pub labels: Vec<String>,
+    pub unparsed: Vec<UnparsedToken>,
+}
+
+/// A token of the quick-add syntax that could not be parsed,
+/// along with its byte range in the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnparsedToken {
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+impl QuickAdd {
+    /// Builds the task of the author from the parsed details.
+    pub fn build_task(&self, author: &Author) -> Result<Task, RusticError> {
+        let mut task = Task::new(
+            &self.name,
+            self.description.as_deref(),
+            None,
+            self.priority.unwrap_or_default().value(),
+            self.labels.iter().map(String::as_str).collect(),
+            author,
+        )?;
+        task.due_date = self.due_date;
+
+        Ok(task)
+    }
+}
+
+/// Parses the quick-add syntax, where `#label` adds a label, `!high` sets
+/// the priority, `due:friday` or `due:"tomorrow 5pm"` sets the due date
+/// relative to `now`, `@desc "text"` sets the description and the
+/// remaining words make up the name. The tokens that cannot be parsed
+/// are kept in `unparsed` instead of the name.
+pub fn parse_quick_add(input: &str, now: &DateTime<Local>) -> QuickAdd {
+    let mut quick_add = QuickAdd::default();
+    let mut name = Vec::new();
+
+    let mut tokens = split_quick_add_tokens(input, &mut quick_add.unparsed).into_iter();
+    while let Some((range, value)) = tokens.next() {
+        let raw = &input[range.clone()];
+        let mut unparsed = |message: String| {
+            quick_add.unparsed.push(UnparsedToken { range: range.clone(), message });
+        };
+
+        if let Some(label) = value.strip_prefix('#') {
+            if label.is_empty() {
+                unparsed("The label after '#' is empty.".to_string());
+            } else {
+                quick_add.labels.push(label.to_string());
+            }
+        } else if let Some(priority) = value.strip_prefix('!') {
+            match Priority::from_name(priority) {
+                Some(priority) => quick_add.priority = Some(priority),
+                None => unparsed(format!("'{raw}' is not a priority, try !low, !medium or !high.")),
+            }
+        } else if raw.to_lowercase().starts_with("due:") {
+            match parse_natural_date(&value[4..], now) {
+                Ok(due_date) => quick_add.due_date = Some(due_date),
+                Err(RusticError::InvalidDueDate(message)) => unparsed(message),
+                Err(e) => unparsed(e.to_string()),
+            }
+        } else if raw.eq_ignore_ascii_case("@desc") {
+            match tokens.next() {
+                Some((_, description)) => quick_add.description = Some(description),
+                None => unparsed("'@desc' needs a description after it.".to_string()),
+            }
+        } else {
+            name.push(value);
+        }
+    }
+    quick_add.name = name.join(" ");
+
+    quick_add
+}
+
+/// Splits the input into its whitespace-separated tokens with their byte
+/// ranges, removing the quotes that keep the text with spaces together.
+fn split_quick_add_tokens(
+    input: &str,
+    unparsed: &mut Vec<UnparsedToken>,
+) -> Vec<(Range<usize>, String)> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut value = String::new();
+        let mut end = input.len();
+        let mut in_quote = false;
+        let mut quote_start = start;
+
+        while let Some(&(index, c)) = chars.peek() {
+            if c.is_whitespace() && !in_quote {
+                end = index;
+                break;
+            }
+
+            if c == '"' {
+                in_quote = !in_quote;
+                quote_start = index;
+            } else {
+                value.push(c);
+            }
+            chars.next();
+        }
+
+        if in_quote {
+            unparsed.push(UnparsedToken {
+                range: quote_start..input.len(),
+                message: "The quote is never closed.".to_string(),
+            });
+            if start < quote_start {
+                tokens.push((start..quote_start, input[start..quote_start].to_string()));
+            }
+        } else {
+            tokens.push((start..end, value));
+        }
+    }
+
+    tokens
 }
@@ -0,0 +1,338 @@
+use std::{io::Write, path::Path};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Timelike};
+use uuid::Uuid;
+
+use super::{
+    task::due_date::parse_natural_date,
+    utils::write_atomically,
+    Priority,
+    Recurrence,
+    Status,
+    Task,
+    TaskBuilder,
+    ToDoList,
+};
+use crate::RusticError;
+
+/// Writes the tasks as the lines of a todo.txt file, such as
+/// `x 2024-06-10 2024-06-01 Call the bank +Finance @phone due:2024-06-12 pri:A`.
+///
+/// The labels are written as projects, except for the ones starting with
+/// `@`, which are contexts. The fields without a todo.txt syntax are written
+/// as the `desc:`, `status:`, `rrule:`, `id:`, `parent:` and `blocked:`
+/// extensions. The spaces in the labels and the description are
+/// percent-encoded, as are the words of the name that would otherwise
+/// be read as a project, a context or one of these extensions.
+pub fn write_todo_txt(tasks: &[Task]) -> String {
+    let mut output = String::new();
+
+    for task in tasks {
+        let mut words: Vec<String> = Vec::new();
+
+        // Completed tasks keep their priority as an extension instead
+        if task.get_status() == &Status::Complete {
+            words.push("x".to_string());
+            if let Some(completed_on) = task.get_completed_on() {
+                words.push(completed_on.format("%Y-%m-%d").to_string());
+                words.push(task.get_created_on().format("%Y-%m-%d").to_string());
+            }
+        } else {
+            words.push(format!("({})", priority_to_letter(task.get_priority())));
+            words.push(task.get_created_on().format("%Y-%m-%d").to_string());
+        }
+
+        words.extend(task.get_name().split_whitespace().map(escape_name_word));
+        for label in task.get_labels() {
+            let label = encode(label);
+            if label.starts_with('@') {
+                words.push(label);
+            } else {
+                words.push(format!("+{label}"));
+            }
+        }
+
+        if let Some(description) = task.get_description() {
+            words.push(format!("desc:{}", encode(description)));
+        }
+        if let Some(due_date) = task.get_due_date() {
+            words.push(format!("due:{}", format_due_date(due_date)));
+        }
+        if task.get_status() == &Status::Complete {
+            words.push(format!("pri:{}", priority_to_letter(task.get_priority())));
+        }
+        if task.get_status() == &Status::InProgress {
+            words.push("status:in-progress".to_string());
+        }
+        if let Some(recurrence) = task.get_recurrence() {
+            words.push(format!("rrule:{recurrence}"));
+        }
+        words.push(format!("id:{}", task.get_id()));
+        if let Some(parent) = task.get_parent() {
+            words.push(format!("parent:{parent}"));
+        }
+        if !task.get_blockers().is_empty() {
+            let blockers: Vec<String> = task.get_blockers().iter().map(Uuid::to_string).collect();
+            words.push(format!("blocked:{}", blockers.join(",")));
+        }
+
+        output.push_str(&words.join(" "));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Reads the tasks from the lines of a todo.txt file, skipping the blank
+/// lines. The projects and the contexts become labels, where the contexts
+/// keep their `@`, and the unknown extensions are kept in the name. The
+/// percent-encoded words are decoded.
+pub fn read_todo_txt(input: &str) -> Result<Vec<Task>, RusticError> {
+    let now = Local::now();
+    let mut tasks: Vec<TaskBuilder> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        tasks.push(read_line(line, &now).map_err(|message| {
+            RusticError::InvalidTodoTxt(index + 1, message)
+        })?);
+    }
+
+    // Rebuild the subtasks from the parents in the file order
+    for index in 0..tasks.len() {
+        let task = tasks[index].as_task();
+        let (id, parent) = (*task.get_id(), task.get_parent().copied());
+        let parent = parent.and_then(|parent| {
+            tasks.iter_mut().find(|other| other.as_task().get_id() == &parent)
+        });
+        if let Some(parent) = parent {
+            if !parent.as_task().get_subtask_ids().contains(&id) {
+                parent.subtask(id);
+            }
+        }
+    }
+
+    Ok(tasks.into_iter().map(TaskBuilder::build).collect())
+}
+
+impl ToDoList {
+    /// Writes the to-do list into a todo.txt file.
+    pub fn write_to_todo_txt(&self, file_path: &Path) -> Result<(), RusticError> {
+        let lines = write_todo_txt(&self.tasks);
+
+        write_atomically(file_path, |file| {
+            file.write_all(lines.as_bytes())?;
+            Ok(())
+        })
+    }
+}
+
+/// Reads the task from the line.
+fn read_line(line: &str, now: &DateTime<Local>) -> Result<TaskBuilder, String> {
+    let mut task = TaskBuilder::new("");
+    task.created_on(*now);
+    let mut words = line.split_whitespace().peekable();
+
+    // The completion mark and its date come first
+    let mut completed_on = None;
+    let is_complete = words.next_if_eq(&"x").is_some();
+    if is_complete {
+        task.status(Status::Complete);
+        completed_on = words.peek().and_then(|word| parse_date(word));
+        if completed_on.is_some() {
+            task.completed_on(completed_on);
+            words.next();
+        }
+    } else if let Some(priority) = words.peek().and_then(|word| parse_priority(word)) {
+        task.priority(priority);
+        words.next();
+    }
+
+    // Followed by the creation date, which cannot be after the completion
+    if let Some(created_on) = words.peek().and_then(|word| parse_date(word)) {
+        task.created_on(created_on);
+        words.next();
+    } else if let Some(completed_on) = completed_on {
+        task.created_on(completed_on);
+    }
+
+    let mut name: Vec<String> = Vec::new();
+    for word in words {
+        if let Some(project) = word.strip_prefix('+').filter(|project| !project.is_empty()) {
+            task.label(decode(project));
+            continue;
+        }
+        if word.len() > 1 && word.starts_with('@') {
+            task.label(decode(word));
+            continue;
+        }
+
+        let Some((key, value)) = word.split_once(':').filter(|(_, value)| !value.is_empty()) else {
+            name.push(decode(word));
+            continue;
+        };
+
+        match key {
+            "desc" => {
+                task.description(Some(decode(value)));
+            },
+            "due" => {
+                let due_date = parse_natural_date(value, now).map_err(|_| {
+                    format!("'{value}' is not a due date.")
+                })?;
+                task.due_date(Some(due_date));
+            },
+            "pri" => {
+                task.priority(parse_priority(&format!("({value})")).ok_or_else(|| {
+                    format!("'{value}' is not a priority from A to Z.")
+                })?);
+            },
+            "status" if value == "in-progress" && !is_complete => {
+                task.status(Status::InProgress);
+            },
+            "rrule" => {
+                task.recurrence(Some(value.parse::<Recurrence>().map_err(|_| {
+                    format!("'{value}' is not a supported repetition rule.")
+                })?));
+            },
+            "id" => {
+                task.id(parse_id(value)?);
+            },
+            "parent" => {
+                task.parent(Some(parse_id(value)?));
+            },
+            "blocked" => {
+                task.blocked_by(value
+                    .split(',')
+                    .map(parse_id)
+                    .collect::<Result<Vec<Uuid>, String>>()?);
+            },
+            _ => name.push(decode(word)),
+        }
+    }
+    task.name(&name.join(" "));
+
+    Ok(task)
+}
+
+/// The keys of the extensions read into the fields of the tasks.
+const EXTENSION_KEYS: &[&str] = &["desc", "due", "pri", "status", "rrule", "id", "parent", "blocked"];
+
+/// Encodes the word of a name, along with the character that would
+/// have it read as a project, a context or an extension.
+fn escape_name_word(word: &str) -> String {
+    let mut word = encode(word);
+
+    if word.starts_with('+') && word.len() > 1 {
+        word.replace_range(..1, "%2B");
+    } else if word.starts_with('@') && word.len() > 1 {
+        word.replace_range(..1, "%40");
+    }
+
+    let is_extension = word
+        .split_once(':')
+        .is_some_and(|(key, value)| !value.is_empty() && EXTENSION_KEYS.contains(&key));
+    if is_extension {
+        word = word.replacen(':', "%3A", 1);
+    }
+
+    word
+}
+
+/// Percent-encodes the whitespace and the `%` signs in the text,
+/// so that it stays a single word.
+fn encode(text: &str) -> String {
+    let mut output = String::new();
+
+    for c in text.chars() {
+        if c.is_whitespace() || c == '%' {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                output.push_str(&format!("%{byte:02X}"));
+            }
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Decodes the percent-encoded text, keeping the text as it is
+/// when it is not valid, such as `50%` written by other tools.
+fn decode(text: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = text.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'%' {
+            bytes.push(byte);
+            rest = tail;
+            continue;
+        }
+
+        let hex = tail
+            .get(..2)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            },
+            None => return text.to_string(),
+        }
+    }
+
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+/// Returns the todo.txt priority letter of the priority.
+fn priority_to_letter(priority: &Priority) -> char {
+    match priority {
+        Priority::High => 'A',
+        Priority::Medium => 'B',
+        Priority::Low => 'C',
+    }
+}
+
+/// Parses the priority such as `(A)`, where
+/// the letters after `C` are low as well.
+fn parse_priority(word: &str) -> Option<Priority> {
+    match word.strip_prefix('(')?.strip_suffix(')')? {
+        "A" => Some(Priority::High),
+        "B" => Some(Priority::Medium),
+        letter if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) => {
+            Some(Priority::Low)
+        },
+        _ => None,
+    }
+}
+
+/// Parses the date such as `2024-06-10` as the start of the day.
+fn parse_date(word: &str) -> Option<DateTime<Local>> {
+    let date = NaiveDate::parse_from_str(word, "%Y-%m-%d").ok()?;
+
+    Local.from_local_datetime(&date.and_time(NaiveTime::MIN)).earliest()
+}
+
+/// Parses the id of a task.
+fn parse_id(value: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|_| format!("'{value}' is not the id of a task."))
+}
+
+/// Formats the due date as a plain date when it is due at the end
+/// of the day, and along with the time otherwise.
+fn format_due_date(due_date: &DateTime<Local>) -> String {
+    let time = due_date.time();
+    if (time.hour(), time.minute(), time.second()) == (23, 59, 59) {
+        due_date.format("%Y-%m-%d").to_string()
+    } else if time.second() == 0 {
+        due_date.format("%Y-%m-%dT%H:%M").to_string()
+    } else {
+        due_date.format("%Y-%m-%dT%H:%M:%S").to_string()
+    }
+}
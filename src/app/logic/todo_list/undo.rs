@@ -0,0 +1,338 @@
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+
+use super::{Priority, Status, Task, ToDoList};
+use crate::RusticError;
+
+/// The number of commands that can be undone.
+const UNDO_LIMIT: usize = 100;
+
+/// The changes to the to-do list that can be undone.
+#[derive(Clone, Debug)]
+pub enum TaskCommand {
+    /// Add the task, as the last subtask of the parent if there is one
+    Add { task: Task, parent: Option<Uuid> },
+    /// Remove the task along with its subtasks
+    Remove(Uuid),
+    /// Change the details of the task
+    Edit(Uuid, TaskEdit),
+    /// Complete the task, or reopen it when it is complete
+    ToggleComplete(Uuid),
+    /// Move the task to the index among its siblings
+    Reorder { id: Uuid, index: usize },
+}
+
+/// The details to change with `TaskCommand::Edit`,
+/// where the unset ones are kept as they are.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskEdit {
+    pub name: Option<String>,
+    /// Set the description, or clear it with `Some(None)`
+    pub description: Option<Option<String>>,
+    pub priority: Option<Priority>,
+    /// Set the due date, or clear it with `Some(None)`
+    pub due_date: Option<Option<DateTime<Local>>>,
+    pub labels: Option<Vec<String>>,
+}
+
+/// The commands that were executed and undone, along with
+/// the changes that take each of them back or bring it back.
+#[derive(Clone, Debug, Default)]
+pub struct UndoStack {
+    done: Vec<AppliedCommand>,
+    undone: Vec<AppliedCommand>,
+}
+
+/// The command along with the change that reverts it, which
+/// also covers its side effects, such as completing the parent task.
+#[derive(Clone, Debug)]
+struct AppliedCommand {
+    command: TaskCommand,
+    revert: Change,
+}
+
+/// The change made through the usual methods of the to-do list, so that
+/// it is recorded in the history and leaves the other tasks alone.
+/// Applying it returns the change that reverts it in turn.
+#[derive(Clone, Debug)]
+enum Change {
+    /// Remove the task along with its subtasks
+    Remove(Uuid),
+    /// Put the removed tasks back where they were
+    Restore(RemovedTree),
+    /// Change the details of the task
+    Edit(Uuid, TaskEdit),
+    /// Set the status of the task and when it was completed
+    SetStatus { id: Uuid, status: Status, completed_on: Option<DateTime<Local>> },
+    /// Move the task to the index among its siblings
+    Move { id: Uuid, index: usize },
+    /// Apply the changes in order, skipping the ones whose tasks are gone
+    Batch(Vec<Change>),
+}
+
+/// The task removed along with its subtasks, and where they were.
+#[derive(Clone, Debug)]
+struct RemovedTree {
+    /// The id of the removed task
+    id: Uuid,
+    /// The tasks in the depth-first order, along with their positions in the list
+    tasks: Vec<(usize, Task)>,
+    /// The parent of the task and the index among its subtasks
+    parent: Option<(Uuid, usize)>,
+    /// The other tasks that were blocked by the removed ones, along with the blockers
+    blocked: Vec<(Uuid, Uuid)>,
+}
+
+impl TaskCommand {
+    /// Applies the command to the to-do list, returning the change that reverts it.
+    fn apply(&self, todo_list: &mut ToDoList) -> Result<Change, RusticError> {
+        match self {
+            TaskCommand::Add { task, parent: Some(parent_id) } => {
+                todo_list.add_subtask(parent_id, task.clone())?;
+                Ok(Change::Remove(*task.get_id()))
+            },
+            TaskCommand::Add { task, parent: None } => {
+                todo_list.add(task.clone());
+                Ok(Change::Remove(*task.get_id()))
+            },
+            TaskCommand::Remove(id) => Change::Remove(*id).apply(todo_list),
+            TaskCommand::Edit(id, edit) => Change::Edit(*id, edit.clone()).apply(todo_list),
+            TaskCommand::ToggleComplete(id) => toggle_complete(todo_list, id),
+            TaskCommand::Reorder { id, index } => {
+                Change::Move { id: *id, index: *index }.apply(todo_list)
+            },
+        }
+    }
+}
+
+impl Change {
+    /// Applies the change to the to-do list, returning the change that reverts it.
+    fn apply(&self, todo_list: &mut ToDoList) -> Result<Change, RusticError> {
+        match self {
+            Change::Remove(id) => remove(todo_list, id),
+            Change::Restore(removed) => restore(todo_list, removed),
+            Change::Edit(id, edit) => {
+                let task = todo_list.get_mut(id)?;
+                let mut revert = TaskEdit::default();
+                if let Some(name) = &edit.name {
+                    revert.name = Some(task.get_name().to_string());
+                    task.rename(name);
+                }
+                if let Some(description) = &edit.description {
+                    revert.description = Some(task.get_description().map(str::to_string));
+                    task.set_description(description.as_deref());
+                }
+                if let Some(priority) = edit.priority {
+                    revert.priority = Some(*task.get_priority());
+                    task.change_priority(priority.value());
+                }
+                if let Some(due_date) = edit.due_date {
+                    revert.due_date = Some(task.get_due_date().copied());
+                    task.set_due_date(due_date);
+                }
+                if let Some(labels) = &edit.labels {
+                    revert.labels = Some(task.get_labels().clone());
+                    task.update_labels(labels.iter().map(String::as_str).collect());
+                }
+                Ok(Change::Edit(*id, revert))
+            },
+            Change::SetStatus { id, status, completed_on } => {
+                let task = todo_list.get_mut(id)?;
+                let revert = Change::SetStatus {
+                    id: *id,
+                    status: *task.get_status(),
+                    completed_on: task.get_completed_on().copied(),
+                };
+                task.update_status(*status);
+                task.set_completed_on(*completed_on);
+                Ok(revert)
+            },
+            Change::Move { id, index } => {
+                let revert = Change::Move { id: *id, index: sibling_index(todo_list, id)? };
+                todo_list.move_task(id, *index)?;
+                Ok(revert)
+            },
+            Change::Batch(changes) => {
+                let mut reverts: Vec<Change> = changes
+                    .iter()
+                    .filter_map(|change| change.apply(todo_list).ok())
+                    .collect();
+                reverts.reverse();
+                Ok(Change::Batch(reverts))
+            },
+        }
+    }
+}
+
+impl UndoStack {
+    /// Returns the command that would be undone next.
+    pub fn next_undo(&self) -> Option<&TaskCommand> {
+        self.done.last().map(|applied| &applied.command)
+    }
+
+    /// Returns the command that would be redone next.
+    pub fn next_redo(&self) -> Option<&TaskCommand> {
+        self.undone.last().map(|applied| &applied.command)
+    }
+
+    /// Forgets every command.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+impl ToDoList {
+    /// Returns an immutable borrow to the `undo_stack` field.
+    pub fn get_undo_stack(&self) -> &UndoStack {
+        &self.undo_stack
+    }
+
+    /// Executes the command so that it can be undone, which clears the
+    /// commands that could be redone. The failed commands leave the
+    /// to-do list as it was.
+    pub fn execute(&mut self, command: TaskCommand) -> Result<(), RusticError> {
+        let revert = command.apply(self)?;
+
+        self.undo_stack.done.push(AppliedCommand { command, revert });
+        if self.undo_stack.done.len() > UNDO_LIMIT {
+            self.undo_stack.done.remove(0);
+        }
+        self.undo_stack.undone.clear();
+
+        Ok(())
+    }
+
+    /// Undoes the last command, returning it, or `None` if there is
+    /// nothing to undo. The command is dropped instead when its task
+    /// has been removed since.
+    pub fn undo(&mut self) -> Option<&TaskCommand> {
+        let applied = self.undo_stack.done.pop()?;
+        let revert = applied.revert.apply(self).ok()?;
+        self.undo_stack.undone.push(AppliedCommand { command: applied.command, revert });
+
+        self.undo_stack.next_redo()
+    }
+
+    /// Redoes the last undone command, returning it, or `None`
+    /// if there is nothing to redo. The command is dropped instead
+    /// when its task has been removed since.
+    pub fn redo(&mut self) -> Option<&TaskCommand> {
+        let applied = self.undo_stack.undone.pop()?;
+        let revert = applied.revert.apply(self).ok()?;
+        self.undo_stack.done.push(AppliedCommand { command: applied.command, revert });
+
+        self.undo_stack.next_undo()
+    }
+}
+
+/// Completes the task, or reopens it when it is complete, returning the
+/// change that reverts the statuses of the task and its parents and
+/// removes the next occurrences added by completing them.
+fn toggle_complete(todo_list: &mut ToDoList, id: &Uuid) -> Result<Change, RusticError> {
+    // Completing or reopening a task only rolls up to its parents
+    let mut statuses = Vec::new();
+    let mut next_id = Some(*id);
+    while let Some(task) = next_id.and_then(|id| todo_list.get(&id).ok()) {
+        statuses.push((*task.get_id(), *task.get_status(), task.get_completed_on().copied()));
+        next_id = task.get_parent().copied();
+    }
+    let count = todo_list.tasks.len();
+
+    if todo_list.get(id)?.get_status() == &Status::Complete {
+        todo_list.reopen(id)?;
+    } else {
+        todo_list.complete(id)?;
+    }
+
+    // The next occurrences are added to the end of the list
+    let mut changes: Vec<Change> = todo_list.tasks[count..]
+        .iter()
+        .map(|task| Change::Remove(*task.get_id()))
+        .collect();
+    for (id, status, completed_on) in statuses {
+        let task = todo_list.get(&id)?;
+        if task.get_status() != &status || task.get_completed_on() != completed_on.as_ref() {
+            changes.push(Change::SetStatus { id, status, completed_on });
+        }
+    }
+
+    Ok(Change::Batch(changes))
+}
+
+/// Removes the task along with its subtasks, returning the change that puts them back.
+fn remove(todo_list: &mut ToDoList, id: &Uuid) -> Result<Change, RusticError> {
+    let parent = todo_list.get(id)?.get_parent().and_then(|parent_id| {
+        let index = todo_list
+            .get(parent_id)
+            .ok()?
+            .get_subtask_ids()
+            .iter()
+            .position(|subtask_id| subtask_id == id)?;
+        Some((*parent_id, index))
+    });
+    let positions: Vec<Uuid> = todo_list.tasks.iter().map(|task| *task.get_id()).collect();
+    let blockers: Vec<(Uuid, Vec<Uuid>)> = todo_list.tasks
+        .iter()
+        .filter(|task| !task.get_blockers().is_empty())
+        .map(|task| (*task.get_id(), task.get_blockers().clone()))
+        .collect();
+
+    let removed = todo_list.remove_tree(id)?;
+    let is_removed = |id: &Uuid| removed.iter().any(|task| task.get_id() == id);
+
+    let blocked = blockers
+        .into_iter()
+        .filter(|(id, _)| !is_removed(id))
+        .flat_map(|(id, blocker_ids)| {
+            blocker_ids
+                .into_iter()
+                .filter(|blocker_id| is_removed(blocker_id))
+                .map(move |blocker_id| (id, blocker_id))
+                .collect::<Vec<(Uuid, Uuid)>>()
+        })
+        .collect();
+    let mut tasks: Vec<(usize, Task)> = removed
+        .into_iter()
+        .filter_map(|task| {
+            let position = positions.iter().position(|id| id == task.get_id())?;
+            Some((position, task))
+        })
+        .collect();
+    tasks.sort_by_key(|(position, _)| *position);
+
+    Ok(Change::Restore(RemovedTree { id: *id, tasks, parent, blocked }))
+}
+
+/// Puts the removed tasks back where they were, returning the change that removes them again.
+fn restore(todo_list: &mut ToDoList, removed: &RemovedTree) -> Result<Change, RusticError> {
+    for (position, task) in &removed.tasks {
+        let position = (*position).min(todo_list.tasks.len());
+        todo_list.tasks.insert(position, task.clone());
+    }
+    if let Some((parent_id, index)) = removed.parent {
+        if let Ok(parent) = todo_list.get_mut(&parent_id) {
+            parent.insert_subtask(index, removed.id);
+        }
+    }
+    for (id, blocker_id) in &removed.blocked {
+        if let Ok(task) = todo_list.get_mut(id) {
+            task.add_blocker(*blocker_id);
+        }
+    }
+
+    Ok(Change::Remove(removed.id))
+}
+
+/// Returns the index of the task among its siblings, like `ToDoList::move_task` takes it.
+fn sibling_index(todo_list: &ToDoList, id: &Uuid) -> Result<usize, RusticError> {
+    let parent = todo_list.get(id)?
+        .get_parent()
+        .and_then(|parent_id| todo_list.get(parent_id).ok());
+    let index = match parent {
+        Some(parent) => parent.get_subtask_ids().iter().position(|subtask_id| subtask_id == id),
+        None => todo_list.get_root_tasks().iter().position(|task| task.get_id() == id),
+    };
+
+    index.ok_or(RusticError::TaskNotFound(*id))
+}
@@ -0,0 +1,213 @@
+use std::{fs, path::Path};
+
+use csv::{ReaderBuilder, WriterBuilder};
+use uuid::Uuid;
+
+use crate::RusticError;
+use super::{Author, Task, TaskBuilder};
+
+/// Reads every author from a CSV file.
+pub fn read_authors_from_csv(file_path: &Path) -> Result<Vec<Author>, RusticError> {
+    // Nothing has been saved yet
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Open the file that contains the login credentials
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .open(file_path)?;
+
+    // Create the reader and add the headers
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(file);
+
+    // Deserialise the authors
+    let mut authors = Vec::new();
+    for result in reader.deserialize() {
+        authors.push(result?);
+    }
+
+    Ok(authors)
+}
+
+/// Reads every task from a CSV file, regardless of the author.
+pub fn read_tasks_from_csv(file_path: &Path) -> Result<Vec<Task>, RusticError> {
+    // Nothing has been saved yet
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Open the file that contains the tasks
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .open(file_path)?;
+
+    // Create the reader and add the headers
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(file);
+
+    // Deserialise the tasks
+    let mut tasks = Vec::new();
+    for result in reader.deserialize() {
+        tasks.push(result?);
+    }
+
+    Ok(tasks)
+}
+
+/// Rewrites the CSV file with the tasks of the author.
+///
+/// Rows are keyed by the task id: edited tasks replace their
+/// old rows, removed tasks are dropped and rows owned by other
+/// authors are kept as they are.
+pub fn save_tasks_to_csv(
+    author: &Author,
+    tasks: &[Task],
+    file_path: &Path,
+) -> Result<(), RusticError> {
+    let existing = read_tasks_from_csv(file_path)?;
+    let mut rows: Vec<&Task> = Vec::new();
+
+    // Update the rows of the tasks and keep the ones owned by other authors
+    for row in &existing {
+        if let Some(task) = tasks
+            .iter()
+            .find(|task| task.get_id() == row.get_id())
+        {
+            rows.push(task);
+        } else if !row.is_created_by(author) {
+            rows.push(row);
+        }
+    }
+
+    // Add the tasks that have not been saved yet
+    for task in tasks {
+        if !existing.iter().any(|row| row.get_id() == task.get_id()) {
+            rows.push(task);
+        }
+    }
+
+    rewrite_tasks_to_csv(rows, file_path)
+}
+
+/// Replaces the names of the authors in the `created_by` field of the
+/// tasks, and in the `changed_by` field of their history, saved before
+/// they were kept by the id of their author, and returns whether any task
+/// was upgraded. A name shared by several authors goes to the earliest
+/// of them.
+pub fn upgrade_task_owners(tasks: &mut [Task], authors: &[Author]) -> bool {
+    let owner_id = |name: &str| -> Option<String> {
+        if Uuid::parse_str(name).is_ok() {
+            return None;
+        }
+
+        authors
+            .iter()
+            .filter(|author| author.get_name() == name)
+            .min_by_key(|author| author.get_created_on())
+            .map(|author| author.get_id().to_string())
+    };
+    let mut is_upgraded = false;
+
+    for task in tasks.iter_mut() {
+        let created_by = owner_id(task.get_created_by());
+        let is_history_upgraded = task
+            .get_history()
+            .iter()
+            .any(|event| owner_id(event.get_changed_by()).is_some());
+        if created_by.is_none() && !is_history_upgraded {
+            continue;
+        }
+
+        let history = task
+            .get_history()
+            .iter()
+            .map(|event| match owner_id(event.get_changed_by()) {
+                Some(changed_by) => event.changed_by(&changed_by),
+                None => event.clone(),
+            })
+            .collect();
+        let mut builder = TaskBuilder::from(std::mem::take(task));
+        builder.history(history);
+        if let Some(created_by) = created_by {
+            builder.created_by(&created_by);
+        }
+        *task = builder.build();
+        is_upgraded = true;
+    }
+
+    is_upgraded
+}
+
+/// Rewrites the CSV file with the given tasks.
+pub fn rewrite_tasks_to_csv<'a, I>(tasks: I, file_path: &Path) -> Result<(), RusticError>
+where
+    I: IntoIterator<Item = &'a Task>,
+{
+    write_atomically(file_path, |file| {
+        let mut writer = WriterBuilder::new()
+            .has_headers(true)
+            .from_writer(file);
+
+        for task in tasks {
+            writer.serialize(task)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    })
+}
+
+/// Checks if the headers of the CSV file match the current task columns,
+/// which is not the case for files written by older versions of the app.
+pub fn has_current_task_headers(file_path: &Path) -> Result<bool, RusticError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(file_path)?;
+    let headers = reader.headers()?.clone();
+
+    // Write a dummy task to get the current headers
+    let mut writer = WriterBuilder::new()
+        .has_headers(true)
+        .from_writer(Vec::new());
+    writer.serialize(Task::default())?;
+    let data = writer.into_inner().map_err(|e| e.into_error())?;
+
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(&data[..]);
+
+    Ok(reader.headers()? == &headers)
+}
+
+/// Writes into a temporary file, syncs it to the disk
+/// and then renames it over the original file.
+pub fn write_atomically<F>(file_path: &Path, write: F) -> Result<(), RusticError>
+where
+    F: FnOnce(&mut fs::File) -> Result<(), RusticError>,
+{
+    // Place the temporary file next to the original one
+    let mut file_name = file_path
+        .file_name()
+        .unwrap_or_default()
+        .to_os_string();
+    file_name.push(".tmp");
+    let temp_path = file_path.with_file_name(file_name);
+
+    // Write and sync the contents, cleaning up on failure
+    let mut file = fs::File::create(&temp_path)?;
+    let result = write(&mut file)
+        .and_then(|_| file.sync_all().map_err(RusticError::from));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    // Replace the original file
+    fs::rename(&temp_path, file_path)?;
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Days, Local};
+
+use super::{Priority, Status, Task, ToDoList};
+use crate::RusticError;
+
+/// The field that the tasks are sorted by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKey {
+    DueDate,
+    Priority,
+    #[default]
+    CreatedOn,
+    Name,
+    Status,
+}
+
+/// The direction that the tasks are sorted in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortKey {
+    /// Compares the tasks by the field in the given order, where the
+    /// tasks without a due date come last and the ties are broken
+    /// by the id so that the order is always the same.
+    pub fn compare(&self, task: &Task, other: &Task, order: SortOrder) -> Ordering {
+        let ordering = match self {
+            SortKey::DueDate => match (task.get_due_date(), other.get_due_date()) {
+                (Some(due_date), Some(other_due_date)) => due_date.cmp(other_due_date),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            SortKey::Priority => task.get_priority().cmp(other.get_priority()),
+            SortKey::CreatedOn => task.get_created_on().cmp(other.get_created_on()),
+            SortKey::Name => task.get_name().to_lowercase().cmp(&other.get_name().to_lowercase()),
+            SortKey::Status => task.get_status().cmp(other.get_status()),
+        };
+
+        let ordering = match order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        };
+
+        ordering.then_with(|| task.get_id().cmp(other.get_id()))
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortKey::DueDate => write!(f, "due_date"),
+            SortKey::Priority => write!(f, "priority"),
+            SortKey::CreatedOn => write!(f, "created_on"),
+            SortKey::Name => write!(f, "name"),
+            SortKey::Status => write!(f, "status"),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = RusticError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "due_date" | "due" => Ok(SortKey::DueDate),
+            "priority" => Ok(SortKey::Priority),
+            "created_on" | "created" => Ok(SortKey::CreatedOn),
+            "name" => Ok(SortKey::Name),
+            "status" => Ok(SortKey::Status),
+            _ => Err(RusticError::InvalidViewOption(name.to_string())),
+        }
+    }
+}
+
+/// The field that the tasks are grouped by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    Status,
+    Priority,
+    Label,
+    DueBucket,
+}
+
+impl GroupBy {
+    /// Returns the groups of the task, where a task
+    /// with several labels belongs to each of them.
+    pub fn keys_of(&self, task: &Task, now: &DateTime<Local>) -> Vec<GroupKey> {
+        match self {
+            GroupBy::Status => vec![GroupKey::Status(*task.get_status())],
+            GroupBy::Priority => vec![GroupKey::Priority(*task.get_priority())],
+            GroupBy::Label if task.get_labels().is_empty() => vec![GroupKey::NoLabel],
+            GroupBy::Label => task
+                .get_labels()
+                .iter()
+                .map(|label| GroupKey::Label(label.clone()))
+                .collect(),
+            GroupBy::DueBucket => {
+                vec![GroupKey::Due(DueBucket::of(task.get_due_date(), now))]
+            },
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Status => write!(f, "status"),
+            GroupBy::Priority => write!(f, "priority"),
+            GroupBy::Label => write!(f, "label"),
+            GroupBy::DueBucket => write!(f, "due"),
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = RusticError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "status" => Ok(GroupBy::Status),
+            "priority" => Ok(GroupBy::Priority),
+            "label" => Ok(GroupBy::Label),
+            "due" | "due_date" | "due_bucket" => Ok(GroupBy::DueBucket),
+            _ => Err(RusticError::InvalidViewOption(name.to_string())),
+        }
+    }
+}
+
+/// The group that a task belongs to, in the order that the groups are shown.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GroupKey {
+    Status(Status),
+    Priority(Priority),
+    Label(String),
+    NoLabel,
+    Due(DueBucket),
+}
+
+impl fmt::Display for GroupKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupKey::Status(status) => write!(f, "{status}"),
+            GroupKey::Priority(priority) => write!(f, "{priority} Priority"),
+            GroupKey::Label(label) => write!(f, "{label}"),
+            GroupKey::NoLabel => write!(f, "No Label"),
+            GroupKey::Due(bucket) => write!(f, "{bucket}"),
+        }
+    }
+}
+
+/// The period that the due date of a task falls in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl DueBucket {
+    /// Returns the period of the due date, where the
+    /// week runs from Monday until the end of Sunday.
+    pub fn of(due_date: Option<&DateTime<Local>>, now: &DateTime<Local>) -> Self {
+        let Some(due_date) = due_date else {
+            return DueBucket::NoDate;
+        };
+
+        let today = now.date_naive();
+        let end_of_week = today
+            .checked_add_days(Days::new(6 - u64::from(today.weekday().num_days_from_monday())))
+            .unwrap_or(today);
+
+        if due_date < now {
+            DueBucket::Overdue
+        } else if due_date.date_naive() == today {
+            DueBucket::Today
+        } else if due_date.date_naive() <= end_of_week {
+            DueBucket::ThisWeek
+        } else {
+            DueBucket::Later
+        }
+    }
+}
+
+impl fmt::Display for DueBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DueBucket::Overdue => write!(f, "Overdue"),
+            DueBucket::Today => write!(f, "Today"),
+            DueBucket::ThisWeek => write!(f, "This Week"),
+            DueBucket::Later => write!(f, "Later"),
+            DueBucket::NoDate => write!(f, "No Date"),
+        }
+    }
+}
+
+impl ToDoList {
+    /// Returns the tasks sorted by the key in the given order.
+    pub fn sorted(&self, key: SortKey, order: SortOrder) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.iter().collect();
+        tasks.sort_by(|task, other| key.compare(task, other, order));
+
+        tasks
+    }
+
+    /// Returns the groups of tasks in their order, with the
+    /// tasks in each group sorted by the key in the given order.
+    pub fn grouped(
+        &self,
+        group_by: GroupBy,
+        key: SortKey,
+        order: SortOrder,
+        now: &DateTime<Local>,
+    ) -> Vec<(GroupKey, Vec<&Task>)> {
+        let mut groups: Vec<(GroupKey, Vec<&Task>)> = Vec::new();
+
+        for task in self.sorted(key, order) {
+            for group_key in group_by.keys_of(task, now) {
+                match groups.iter_mut().find(|(other_key, _)| other_key == &group_key) {
+                    Some((_, tasks)) => tasks.push(task),
+                    None => groups.push((group_key, vec![task])),
+                }
+            }
+        }
+        groups.sort_by(|(group_key, _), (other_key, _)| group_key.cmp(other_key));
+
+        groups
+    }
+}
@@ -1,14 +1,31 @@
 /// The module that implements the AppWindow object.
 mod imp;
 
-use std::path::Path;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::rc::Rc;
 
 use glib::{clone, Object};
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
-use gtk::{gio, glib, Application, ListItem, NoSelection, SignalListItemFactory};
+use gtk::{
+    gio, glib, pango, Application, CustomFilter, CustomSorter, FilterChange, FilterListModel, Label,
+    ListHeader, ListItem, NoSelection, SignalListItemFactory, SortListModel, SorterChange,
+    TreeListModel, TreeListRow, TreeListRowSorter,
+};
 
-use crate::{Author, LoginState, TaskBox, TaskEntry};
+use chrono::Local;
+use pango::{AttrColor, AttrInt, AttrList};
+use uuid::Uuid;
+
+use crate::app::todo_list::task::utils::{parse_quick_add, QuickAdd};
+use crate::{
+    Author, GroupBy, GroupKey, LoginState, RusticError, Session, SortKey, SortOrder,
+    Status, Storage, Task, TaskBox, TaskCommand, TaskEntry, ToDoList,
+};
+
+/// The number of seconds that the undo banner is shown for.
+const UNDO_BANNER_SECONDS: u32 = 5;
 
 glib::wrapper! {
     /// Wrapper for the AppWindow GObject subclass.
@@ -20,11 +37,12 @@ glib::wrapper! {
 
 impl AppWindow {
     /// Creates a new instance of AppWindow.
-    pub fn new(app: &Application) -> Self {
+    pub fn new(app: &Application, storage: Rc<dyn Storage>) -> Self {
         let window: AppWindow = Object::builder()
             .property("application", app)
             .build();
 
+        window.imp().storage.replace(Some(storage));
         window.imp().todo_list.set_visible(false);
 
         window
@@ -39,18 +57,489 @@ impl AppWindow {
             .expect("Error while loading current tasks.")
     }
 
+    /// Retrieves the storage backend of the window.
+    fn get_storage(&self) -> Rc<dyn Storage> {
+        self.imp()
+            .storage
+            .borrow()
+            .clone()
+            .expect("Error while loading the storage backend.")
+    }
+
     /// Creates a new task and adds it to the task list.
     fn create_new_task(&self) {
-        let buffer = self.imp().task_entry.buffer();
-        let content = buffer.text().to_string();
-        if content.is_empty() {
+        // Keep the text while some of its tokens cannot be parsed
+        let quick_add = self.parse_task_entry();
+        if quick_add.name.is_empty() || !quick_add.unparsed.is_empty() {
             return;
         }
-        buffer.set_text("");
-        
-        // Create a new task entry
-        let task_entry = TaskEntry::new(false, content);
-        self.get_current_tasks().append(&task_entry);
+
+        let mut session = self.imp().session.borrow_mut();
+        let Some(todo_list) = session.as_mut().map(Session::get_todo_list_mut) else {
+            return;
+        };
+
+        // Create a new task owned by the logged-in author
+        let task = match quick_add.build_task(todo_list.get_author()) {
+            Ok(task) => task,
+            Err(e) => {
+                drop(session);
+                self.show_error(&e);
+                return;
+            },
+        };
+        self.imp().task_entry.buffer().set_text("");
+
+        // Add the task and its entry, then save the task
+        let id = *task.get_id();
+        if let Err(e) = todo_list.execute(TaskCommand::Add { task, parent: None }) {
+            drop(session);
+            self.show_error(&e);
+            return;
+        }
+        let task_entry = todo_list
+            .get(&id)
+            .map(|task| self.create_task_entry(todo_list, task));
+        drop(session);
+
+        // The sorters read the session while the entry is added
+        if let Ok(task_entry) = task_entry {
+            self.get_current_tasks().append(&task_entry);
+        }
+
+        self.update_undo_actions();
+        self.save_tasks();
+    }
+
+    /// Parses the quick-add syntax of the task entry and highlights
+    /// the tokens that cannot be parsed, explaining them in the tooltip.
+    fn parse_task_entry(&self) -> QuickAdd {
+        let task_entry = self.imp().task_entry.get();
+        let quick_add = parse_quick_add(&task_entry.text(), &Local::now());
+
+        let attributes = AttrList::new();
+        for token in &quick_add.unparsed {
+            let (start, end) = (token.range.start as u32, token.range.end as u32);
+
+            let mut underline = AttrInt::new_underline(pango::Underline::Error);
+            underline.set_start_index(start);
+            underline.set_end_index(end);
+            attributes.insert(underline);
+
+            let mut foreground = AttrColor::new_foreground(0xc0c0, 0x1c1c, 0x2828);
+            foreground.set_start_index(start);
+            foreground.set_end_index(end);
+            attributes.insert(foreground);
+        }
+        task_entry.set_attributes(&attributes);
+
+        let messages: Vec<&str> = quick_add.unparsed
+            .iter()
+            .map(|token| &token.message[..])
+            .collect();
+        let tooltip = (!messages.is_empty()).then(|| messages.join("\n"));
+        task_entry.set_tooltip_text(tooltip.as_deref());
+
+        quick_add
+    }
+
+    /// Creates an entry for the task along with the entries of its
+    /// subtasks, and keeps the task updated when the entry is toggled.
+    fn create_task_entry(&self, todo_list: &ToDoList, task: &Task) -> TaskEntry {
+        let task_entry = TaskEntry::from_task(task);
+
+        let children = task_entry.children();
+        for subtask in todo_list.get_subtasks(task.get_id()).unwrap_or_default() {
+            children.append(&self.create_task_entry(todo_list, subtask));
+        }
+
+        task_entry.connect_notify_local(
+            Some("completed"),
+            clone!(@weak self as window => move |task_entry, _| {
+                window.update_task_completion(task_entry);
+            }),
+        );
+
+        task_entry
+    }
+
+    /// Rebuilds the task store from the task tree of the current session.
+    fn reload_task_entries(&self) {
+        let task_store = self.get_current_tasks();
+        task_store.remove_all();
+
+        let session = self.imp().session.borrow();
+        let Some(todo_list) = session.as_ref().map(Session::get_todo_list) else {
+            return;
+        };
+
+        for task in todo_list.get_root_tasks() {
+            task_store.append(&self.create_task_entry(todo_list, task));
+        }
+        drop(session);
+
+        self.update_search();
+    }
+
+    /// Updates the entries in the store and their subtask entries
+    /// with the current details of the tasks.
+    fn refresh_task_entries(&self, task_store: &gio::ListStore, tasks: &[Task]) {
+        for task_entry in task_store.iter::<TaskEntry>().flatten() {
+            let task = tasks
+                .iter()
+                .find(|task| task.get_id().to_string() == task_entry.task_id());
+            if let Some(task) = task {
+                task_entry.update(task);
+            }
+
+            self.refresh_task_entries(&task_entry.children(), tasks);
+        }
+    }
+
+    /// Updates the completion of the task mirrored by the entry and saves it.
+    fn update_task_completion(&self, task_entry: &TaskEntry) {
+        let Ok(id) = Uuid::parse_str(&task_entry.task_id()) else {
+            return;
+        };
+
+        let mut session = self.imp().session.borrow_mut();
+        let Some(todo_list) = session.as_mut().map(Session::get_todo_list_mut) else {
+            return;
+        };
+
+        // Skip the entries that are refreshed to match their task
+        let Ok(task) = todo_list.get(&id) else {
+            return;
+        };
+        if (task.get_status() == &Status::Complete) == task_entry.completed() {
+            return;
+        }
+        let name = task.get_name().to_string();
+
+        // Complete or reopen the task, which rolls up to its parents
+        let task_count = todo_list.get_tasks().len();
+        let result = todo_list.execute(TaskCommand::ToggleComplete(id));
+        let tasks = todo_list.get_tasks().clone();
+        drop(session);
+
+        match result {
+            // Show the next occurrence of the recurring task
+            Ok(()) if tasks.len() != task_count => self.reload_task_entries(),
+            Ok(()) => {
+                self.refresh_task_entries(&self.get_current_tasks(), &tasks);
+                self.update_search();
+                self.update_sorting();
+            },
+            // Put the entry of the blocked task back as it was
+            Err(e) => {
+                self.show_error(&e);
+                self.refresh_task_entries(&self.get_current_tasks(), &tasks);
+                return;
+            },
+        }
+
+        let verb = if task_entry.completed() { "Completed" } else { "Reopened" };
+        self.show_undo_banner(&format!("{verb} “{name}”"));
+        self.update_undo_actions();
+        self.save_tasks();
+    }
+
+    /// Removes the task along with its subtasks and saves the tasks.
+    fn remove_task(&self, id: &Uuid) {
+        let mut session = self.imp().session.borrow_mut();
+        let Some(todo_list) = session.as_mut().map(Session::get_todo_list_mut) else {
+            return;
+        };
+
+        let Ok(name) = todo_list.get(id).map(|task| task.get_name().to_string()) else {
+            return;
+        };
+        let result = todo_list.execute(TaskCommand::Remove(*id));
+        drop(session);
+
+        if let Err(e) = result {
+            self.show_error(&e);
+            return;
+        }
+
+        self.reload_task_entries();
+        self.show_undo_banner(&format!("Removed “{name}”"));
+        self.update_undo_actions();
+        self.save_tasks();
+    }
+
+    /// Undoes the last change to the tasks, or redoes the last undone
+    /// change, and saves the tasks.
+    fn undo_or_redo(&self, redo: bool) {
+        let mut session = self.imp().session.borrow_mut();
+        let Some(todo_list) = session.as_mut().map(Session::get_todo_list_mut) else {
+            return;
+        };
+
+        let changed = if redo {
+            todo_list.redo().is_some()
+        } else {
+            todo_list.undo().is_some()
+        };
+        drop(session);
+
+        if changed {
+            self.hide_undo_banner();
+            self.reload_task_entries();
+            self.save_tasks();
+        }
+        self.update_undo_actions();
+    }
+
+    /// Enables the undo and redo actions when there is something to undo or redo.
+    fn update_undo_actions(&self) {
+        let session = self.imp().session.borrow();
+        let undo_stack = session
+            .as_ref()
+            .map(|session| session.get_todo_list().get_undo_stack());
+        let can_undo = undo_stack.is_some_and(|undo_stack| undo_stack.next_undo().is_some());
+        let can_redo = undo_stack.is_some_and(|undo_stack| undo_stack.next_redo().is_some());
+        drop(session);
+
+        for (name, enabled) in [("undo", can_undo), ("redo", can_redo)] {
+            if let Some(action) = self.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    /// Shows the banner that offers to undo the change for a few seconds.
+    fn show_undo_banner(&self, message: &str) {
+        self.imp().undo_label.set_label(message);
+        self.imp().undo_banner.set_reveal_child(true);
+
+        // Restart the countdown of the previous banner
+        if let Some(source_id) = self.imp().undo_timeout.take() {
+            source_id.remove();
+        }
+        let source_id = glib::timeout_add_seconds_local(
+            UNDO_BANNER_SECONDS,
+            clone!(@weak self as window => @default-return glib::ControlFlow::Break, move || {
+                window.imp().undo_timeout.take();
+                window.imp().undo_banner.set_reveal_child(false);
+                glib::ControlFlow::Break
+            }),
+        );
+        self.imp().undo_timeout.replace(Some(source_id));
+    }
+
+    /// Hides the banner that offers to undo the change.
+    fn hide_undo_banner(&self) {
+        if let Some(source_id) = self.imp().undo_timeout.take() {
+            source_id.remove();
+        }
+        self.imp().undo_banner.set_reveal_child(false);
+    }
+
+    /// Shows the error in the banner below the task list until it is dismissed.
+    fn show_error(&self, error: &RusticError) {
+        self.imp().error_label.set_label(&describe_error(error));
+        self.imp().error_banner.set_reveal_child(true);
+    }
+
+    /// Hides the banner that shows the last error.
+    fn hide_error(&self) {
+        self.imp().error_banner.set_reveal_child(false);
+    }
+
+    /// Starts the session of the author and shows their to-do list.
+    fn start_session(&self, author: Author) {
+        let session = match Session::start(author, self.get_storage().as_ref()) {
+            Ok(session) => session,
+            Err(e) => {
+                self.show_login_error(&describe_error(&e));
+                return;
+            },
+        };
+
+        self.imp().author_salutation.set_label(
+            &format!("Hello, {}!", session.get_author().get_name())
+        );
+
+        // Load the task tree into the task store
+        self.imp().session.replace(Some(session));
+        self.reload_task_entries();
+        self.update_undo_actions();
+
+        self.clear_login_form();
+        self.imp().login_form.set_visible(false);
+        self.imp().todo_list.set_visible(true);
+    }
+
+    /// Saves the tasks, ends the current session and shows the login form.
+    fn end_session(&self) {
+        let result = match self.imp().session.take() {
+            Some(session) => session.end(self.get_storage().as_ref()),
+            None => Ok(()),
+        };
+
+        self.get_current_tasks().remove_all();
+        self.imp().search_entry.set_text("");
+        self.hide_undo_banner();
+        self.hide_error();
+        self.update_undo_actions();
+        self.clear_login_form();
+
+        // Tell the author that the tasks could not be saved on the way out
+        if let Err(e) = result {
+            self.show_login_error(&describe_error(&e));
+        }
+
+        self.imp().todo_list.set_visible(false);
+        self.imp().login_form.set_visible(true);
+    }
+
+    /// Filters the task list with the query from the search entry, keeping
+    /// the parents of the matching tasks to show them in the tree.
+    fn update_search(&self) {
+        let search_entry = self.imp().search_entry.get();
+        let text = search_entry.text();
+
+        let session = self.imp().session.borrow();
+        let Some(todo_list) = session.as_ref().map(Session::get_todo_list) else {
+            return;
+        };
+
+        let search_matches = match todo_list.query(&text) {
+            Ok(_) if text.trim().is_empty() => None,
+            Ok(tasks) => {
+                let mut search_matches = HashSet::new();
+                for task in tasks {
+                    let mut current = Some(task);
+                    while let Some(task) = current {
+                        if !search_matches.insert(task.get_id().to_string()) {
+                            break;
+                        }
+                        current = task.get_parent().and_then(|id| todo_list.get(id).ok());
+                    }
+                }
+                Some(search_matches)
+            },
+            // Keep the previous results while the query is invalid
+            Err(e) => {
+                search_entry.add_css_class("error");
+                search_entry.set_tooltip_text(Some(&describe_error(&e)));
+                return;
+            },
+        };
+        drop(session);
+
+        search_entry.remove_css_class("error");
+        search_entry.set_tooltip_text(None);
+        self.imp().search_matches.replace(search_matches);
+
+        if let Some(task_filter) = self.imp().task_filter.borrow().as_ref() {
+            task_filter.changed(FilterChange::Different);
+        }
+    }
+
+    /// Sorts the task list again and shows the section headers
+    /// when the tasks are grouped.
+    fn update_sorting(&self) {
+        for sorter in [&self.imp().task_sorter, &self.imp().section_sorter] {
+            if let Some(sorter) = sorter.borrow().as_ref() {
+                sorter.changed(SorterChange::Different);
+            }
+        }
+
+        let header_factory = self.imp().group_by.get().map(|_| self.create_header_factory());
+        self.imp().task_list.set_header_factory(header_factory.as_ref());
+    }
+
+    /// Compares the tasks of the entries by the current sort key.
+    fn compare_task_entries(&self, item: &glib::Object, other: &glib::Object) -> Ordering {
+        let session = self.imp().session.borrow();
+        let Some(todo_list) = session.as_ref().map(Session::get_todo_list) else {
+            return Ordering::Equal;
+        };
+
+        match (find_task(todo_list, item), find_task(todo_list, other)) {
+            (Some(task), Some(other_task)) => self.imp().sort_key.get().compare(
+                task,
+                other_task,
+                self.imp().sort_order.get(),
+            ),
+            _ => Ordering::Equal,
+        }
+    }
+
+    /// Compares the groups of the rows, where the subtasks
+    /// are kept in the group of their top-level task.
+    fn compare_task_groups(&self, row: &glib::Object, other: &glib::Object) -> Ordering {
+        self.describe_group(row).cmp(&self.describe_group(other))
+    }
+
+    /// Returns the group of the row by the current grouping, where a task
+    /// with several labels is shown under the first one alphabetically.
+    fn describe_group(&self, row: &glib::Object) -> Option<GroupKey> {
+        let group_by = self.imp().group_by.get()?;
+
+        // Find the top-level row of the subtasks
+        let mut row = row.downcast_ref::<TreeListRow>()?.clone();
+        while let Some(parent) = row.parent() {
+            row = parent;
+        }
+
+        let session = self.imp().session.borrow();
+        let todo_list = session.as_ref().map(Session::get_todo_list)?;
+        let task = find_task(todo_list, &row.item()?)?;
+
+        group_by.keys_of(task, &Local::now()).into_iter().min()
+    }
+
+    /// Creates the factory for the headers of the task groups.
+    fn create_header_factory(&self) -> SignalListItemFactory {
+        let factory = SignalListItemFactory::new();
+
+        factory.connect_setup(move |_, list_header| {
+            let label = Label::builder()
+                .xalign(0.0)
+                .margin_top(12)
+                .margin_start(12)
+                .css_classes(["heading"])
+                .build();
+            list_header
+                .downcast_ref::<ListHeader>()
+                .expect("Needs to be ListHeader")
+                .set_child(Some(&label));
+        });
+
+        factory.connect_bind(clone!(@weak self as window => move |_, list_header| {
+            let list_header = list_header
+                .downcast_ref::<ListHeader>()
+                .expect("Needs to be ListHeader");
+
+            let group = list_header
+                .item()
+                .and_then(|row| window.describe_group(&row))
+                .map(|group| group.to_string())
+                .unwrap_or_default();
+
+            list_header
+                .child()
+                .and_downcast::<Label>()
+                .expect("The child has to be a `Label`.")
+                .set_label(&group);
+        }));
+
+        factory
+    }
+
+    /// Saves the tasks of the logged-in author.
+    pub fn save_tasks(&self) {
+        let result = match self.imp().session.borrow().as_ref() {
+            Some(session) => session.save(self.get_storage().as_ref()),
+            None => Ok(()),
+        };
+
+        if let Err(e) = result {
+            self.show_error(&e);
+        }
     }
 
     /// Sets up the task list model and binds it to the task store.
@@ -62,11 +551,52 @@ impl AppWindow {
         self.imp()
             .task_store
             .replace(Some(model));
+
+        // Expand the tasks into their subtasks
+        let tree_model = TreeListModel::new(
+            self.get_current_tasks(),
+            false,
+            true,
+            |item| {
+                let children = item
+                    .downcast_ref::<TaskEntry>()
+                    .expect("The item has to be a `TaskEntry`.")
+                    .children();
+                (children.n_items() > 0).then(|| children.upcast())
+            },
+        );
         
-        // Set the storage model
-        let selection_model = NoSelection::new(
-            Some(self.get_current_tasks())
+        // Keep the rows matching the search
+        let task_filter = CustomFilter::new(clone!(@weak self as window => @default-return true, move |item| {
+            let Some(search_matches) = window.imp().search_matches.borrow().clone() else {
+                return true;
+            };
+
+            item.downcast_ref::<TreeListRow>()
+                .and_then(TreeListRow::item)
+                .and_downcast::<TaskEntry>()
+                .is_some_and(|task_entry| search_matches.contains(&task_entry.task_id()))
+        }));
+        let filter_model = FilterListModel::new(Some(tree_model), Some(task_filter.clone()));
+        self.imp().task_filter.replace(Some(task_filter));
+
+        // Sort the tasks among their siblings and the rows into their groups
+        let task_sorter = CustomSorter::new(clone!(@weak self as window => @default-return gtk::Ordering::Equal, move |item, other| {
+            window.compare_task_entries(item, other).into()
+        }));
+        let section_sorter = CustomSorter::new(clone!(@weak self as window => @default-return gtk::Ordering::Equal, move |row, other| {
+            window.compare_task_groups(row, other).into()
+        }));
+        let sort_model = SortListModel::new(
+            Some(filter_model),
+            Some(TreeListRowSorter::new(Some(task_sorter.clone()))),
         );
+        sort_model.set_section_sorter(Some(&section_sorter));
+        self.imp().task_sorter.replace(Some(task_sorter));
+        self.imp().section_sorter.replace(Some(section_sorter));
+
+        // Set the storage model
+        let selection_model = NoSelection::new(Some(sort_model));
         self.imp()
             .task_list
             .set_model(Some(&selection_model));
@@ -80,6 +610,12 @@ impl AppWindow {
                 window.create_new_task();
             }));
         
+        self.imp()
+            .task_entry
+            .connect_changed(clone!(@weak self as window => move |_| {
+                window.parse_task_entry();
+            }));
+
         self.imp()
             .task_entry
             .connect_icon_release(clone!(@weak self as window => move |_, _| {
@@ -89,46 +625,228 @@ impl AppWindow {
         self.imp()
             .login_button
             .connect_clicked(clone!(@weak self as window => move |_| {
-                let name = window.imp().name.text().trim().to_string();
-                let email = window.imp().email.text().trim().to_string();
-                let password = window.imp().password.text().trim().to_string();
-
-                if !name.is_empty() && !email.is_empty() && !password.is_empty() {
-                    let file_path = Path::new("./data/author.csv");
-
-                    // Build the author
-                    let author = Author::build(&name[..], &email[..], &password[..])
-                        .expect("Login unsuccessful.");
-                    
-                    // Try to login
-                    let login_state = Author::login(&email[..], &password[..], &file_path);
-                    match login_state {
-                        Ok(state @ LoginState::InvalidCredentials) => {
-                            eprintln!("The user credentials are invalid.");
-                            println!("login_state={:?}", state);
-                            return;
-                        }
-                        Ok(state @ LoginState::LoggedIn) => {
-                            println!("login_state={:?}", state);
-                            window.imp().login_form.set_visible(false);
-                            window.imp().todo_list.set_visible(true);
-                        },
-                        Ok(state @ LoginState::DoesNotExist) => {
-                            eprintln!("The user does not exist.");
-                            println!("login_state={:?}", state);
-                            author.write_to_csv(file_path)
-                                .expect("Error while saving login info.");
-                        },
-                        Err(e) => {
-                            eprintln!("Error: {e}");
-                        }
-                    }
-                    
-                    window.imp().author_salutation.set_label(
-                        &format!("Hello, {}!", author.get_name())
-                    );
-                }
+                window.sign_in();
+            }));
+
+        self.imp()
+            .password
+            .connect_activate(clone!(@weak self as window => move |_| {
+                window.sign_in();
+            }));
+
+        self.imp()
+            .register_button
+            .connect_clicked(clone!(@weak self as window => move |_| {
+                window.create_account();
             }));
+
+        self.imp()
+            .confirm_password
+            .connect_activate(clone!(@weak self as window => move |_| {
+                window.create_account();
+            }));
+
+        self.imp()
+            .search_entry
+            .connect_search_changed(clone!(@weak self as window => move |_| {
+                window.update_search();
+            }));
+
+        // Clear the error when switching between the login modes
+        self.imp()
+            .login_stack
+            .connect_visible_child_name_notify(clone!(@weak self as window => move |_| {
+                window.imp().login_error.set_visible(false);
+            }));
+    }
+
+    /// Signs in with the credentials from the sign in form.
+    fn sign_in(&self) {
+        let email = self.imp().email.text().trim().to_string();
+        let password = self.imp().password.text().trim().to_string();
+
+        if email.is_empty() || password.is_empty() {
+            self.show_login_error("Please enter your email address and password.");
+            return;
+        }
+
+        // Try to login
+        let login_state = Author::login(&email[..], &password[..], self.get_storage().as_ref());
+        match login_state {
+            Ok(LoginState::LoggedIn(author)) => {
+                self.start_session(author);
+            },
+            Ok(LoginState::InvalidCredentials) => {
+                self.show_login_error("The password is incorrect.");
+            },
+            Ok(LoginState::DoesNotExist) => {
+                self.show_login_error("There is no account with this email address.");
+            },
+            Err(e) => {
+                self.show_login_error(&describe_error(&e));
+            },
+        }
+    }
+
+    /// Creates a new account with the details from the registration form.
+    fn create_account(&self) {
+        let name = self.imp().name.text().trim().to_string();
+        let email = self.imp().register_email.text().trim().to_string();
+        let password = self.imp().register_password.text().trim().to_string();
+        let confirm_password = self.imp().confirm_password.text().trim().to_string();
+
+        if name.is_empty() || email.is_empty() || password.is_empty() {
+            self.show_login_error("Please fill in your name, email address and password.");
+            return;
+        }
+
+        if password != confirm_password {
+            self.show_login_error("The passwords do not match.");
+            return;
+        }
+
+        // Register and login as the new author
+        match Author::register(&name[..], &email[..], &password[..], self.get_storage().as_ref()) {
+            Ok(author) => {
+                self.start_session(author);
+            },
+            Err(e) => {
+                self.show_login_error(&describe_error(&e));
+            },
+        }
+    }
+
+    /// Shows the error message below the login form.
+    fn show_login_error(&self, message: &str) {
+        self.imp().login_error.set_label(message);
+        self.imp().login_error.set_visible(true);
+    }
+
+    /// Clears the entries and the error of the login form.
+    fn clear_login_form(&self) {
+        for entry in [
+            &self.imp().password,
+            &self.imp().register_password,
+            &self.imp().confirm_password,
+        ] {
+            entry.set_text("");
+        }
+
+        self.imp().login_error.set_visible(false);
+    }
+
+    /// Sets up the actions of the window.
+    fn setup_actions(&self) {
+        // Log out of the current session
+        let action_logout = gio::ActionEntry::builder("logout")
+            .activate(move |window: &Self, _, _| {
+                window.end_session();
+            })
+            .build();
+
+        // Sort the tasks by the chosen key
+        let action_sort_by = gio::ActionEntry::builder("sort-by")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state(SortKey::default().to_string().to_variant())
+            .activate(move |window: &Self, action, parameter| {
+                let Some(name) = parameter.and_then(|parameter| parameter.get::<String>()) else {
+                    return;
+                };
+                let Ok(sort_key) = name.parse::<SortKey>() else {
+                    return;
+                };
+
+                window.imp().sort_key.set(sort_key);
+                action.set_state(&name.to_variant());
+                window.update_sorting();
+            })
+            .build();
+
+        // Flip the order of the tasks
+        let action_sort_descending = gio::ActionEntry::builder("sort-descending")
+            .state(false.to_variant())
+            .activate(move |window: &Self, action, _| {
+                let descending = !action
+                    .state()
+                    .and_then(|state| state.get::<bool>())
+                    .unwrap_or_default();
+                let sort_order = if descending {
+                    SortOrder::Descending
+                } else {
+                    SortOrder::Ascending
+                };
+
+                window.imp().sort_order.set(sort_order);
+                action.set_state(&descending.to_variant());
+                window.update_sorting();
+            })
+            .build();
+
+        // Group the tasks under the section headers
+        let action_group_by = gio::ActionEntry::builder("group-by")
+            .parameter_type(Some(&String::static_variant_type()))
+            .state("none".to_variant())
+            .activate(move |window: &Self, action, parameter| {
+                let Some(name) = parameter.and_then(|parameter| parameter.get::<String>()) else {
+                    return;
+                };
+                let group_by = match &name[..] {
+                    "none" => None,
+                    name => match name.parse::<GroupBy>() {
+                        Ok(group_by) => Some(group_by),
+                        Err(_) => return,
+                    },
+                };
+
+                window.imp().group_by.set(group_by);
+                action.set_state(&name.to_variant());
+                window.update_sorting();
+            })
+            .build();
+
+        // Undo and redo the changes to the tasks
+        let action_undo = gio::ActionEntry::builder("undo")
+            .activate(move |window: &Self, _, _| {
+                window.undo_or_redo(false);
+            })
+            .build();
+        let action_redo = gio::ActionEntry::builder("redo")
+            .activate(move |window: &Self, _, _| {
+                window.undo_or_redo(true);
+            })
+            .build();
+
+        // Dismiss the banner that shows the last error
+        let action_dismiss_error = gio::ActionEntry::builder("dismiss-error")
+            .activate(move |window: &Self, _, _| {
+                window.hide_error();
+            })
+            .build();
+
+        // Remove the task with the id from the task box
+        let action_remove_task = gio::ActionEntry::builder("remove-task")
+            .parameter_type(Some(&String::static_variant_type()))
+            .activate(move |window: &Self, _, parameter| {
+                let id = parameter
+                    .and_then(|parameter| parameter.get::<String>())
+                    .and_then(|id| Uuid::parse_str(&id).ok());
+                if let Some(id) = id {
+                    window.remove_task(&id);
+                }
+            })
+            .build();
+
+        self.add_action_entries([
+            action_undo,
+            action_redo,
+            action_remove_task,
+            action_dismiss_error,
+            action_logout,
+            action_sort_by,
+            action_sort_descending,
+            action_group_by,
+        ]);
+        self.update_undo_actions();
     }
 
     /// Sets up the factory for creating and binding task items in the task list.
@@ -144,12 +862,17 @@ impl AppWindow {
         });
 
         factory.connect_bind(move |_, list_item| {
-            let task_entry = list_item
+            let list_row = list_item
                 .downcast_ref::<ListItem>()
                 .expect("Needs to be ListItem")
+                .item()
+                .and_downcast::<TreeListRow>()
+                .expect("The item has to be a `TreeListRow`.");
+
+            let task_entry = list_row
                 .item()
                 .and_downcast::<TaskEntry>()
-                .expect("The item has to be a `TaskEntry`.");
+                .expect("The row item has to be a `TaskEntry`.");
 
             let task_box = list_item
                 .downcast_ref::<ListItem>()
@@ -158,7 +881,7 @@ impl AppWindow {
                 .and_downcast::<TaskBox>()
                 .expect("The child has to be a `TaskBox`.");
             
-            task_box.bind(&task_entry);
+            task_box.bind(&task_entry, &list_row);
         });
 
         factory.connect_unbind(move |_, list_item| {
@@ -175,3 +898,44 @@ impl AppWindow {
         self.imp().task_list.set_factory(Some(&factory));
     }
 }
+
+/// Finds the task mirrored by the entry in the to-do list.
+fn find_task<'a>(todo_list: &'a ToDoList, item: &glib::Object) -> Option<&'a Task> {
+    let id = Uuid::parse_str(&item.downcast_ref::<TaskEntry>()?.task_id()).ok()?;
+    todo_list.get(&id).ok()
+}
+
+/// Describes the error in a message that can be shown to the user.
+fn describe_error(error: &RusticError) -> String {
+    match error {
+        RusticError::InvalidEmail => {
+            "Please enter a valid email address.".to_string()
+        },
+        RusticError::AuthorExists => {
+            "An account with this email address already exists.".to_string()
+        },
+        RusticError::TaskBlocked(_) => {
+            "The task is blocked by tasks that are not complete yet.".to_string()
+        },
+        RusticError::TaskNotFound(_) => {
+            "The task no longer exists.".to_string()
+        },
+        RusticError::DependencyCycle(..) => {
+            "The tasks cannot block each other.".to_string()
+        },
+        RusticError::InvalidDueDate(message) => {
+            format!("The due date cannot be read: {message}")
+        },
+        RusticError::InvalidQuery(position, message) => {
+            format!("The search cannot be read at position {position}: {message}")
+        },
+        RusticError::InvalidRecurrence(rule) => {
+            format!("The repetition rule “{rule}” cannot be read.")
+        },
+        RusticError::InvalidICalendar(line, message)
+        | RusticError::InvalidTodoTxt(line, message) => {
+            format!("The file cannot be read at line {line}: {message}")
+        },
+        e => format!("Something went wrong: {e}"),
+    }
+}
@@ -1,8 +1,17 @@
-use std::cell::RefCell;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    rc::Rc,
+};
 
 use glib::subclass::InitializingObject;
 use gtk::subclass::prelude::*;
-use gtk::{gio, glib, ApplicationWindow, Button, CompositeTemplate, Entry, Label, ListView};
+use gtk::{
+    gio, glib, ApplicationWindow, Button, CompositeTemplate, CustomFilter, CustomSorter, Entry,
+    Label, ListView, Revealer, SearchEntry, Stack,
+};
+
+use crate::{GroupBy, Session, SortKey, SortOrder, Storage};
 
 /// The structure that represents the main application window.
 #[derive(CompositeTemplate, Default)]
@@ -15,7 +24,7 @@ pub struct AppWindow {
     #[template_child]
     pub login_salutation: TemplateChild<Label>,
     #[template_child]
-    pub name: TemplateChild<Entry>,
+    pub login_stack: TemplateChild<Stack>,
     #[template_child]
     pub email: TemplateChild<Entry>,
     #[template_child]
@@ -23,14 +32,46 @@ pub struct AppWindow {
     #[template_child]
     pub login_button: TemplateChild<Button>,
     #[template_child]
+    pub name: TemplateChild<Entry>,
+    #[template_child]
+    pub register_email: TemplateChild<Entry>,
+    #[template_child]
+    pub register_password: TemplateChild<Entry>,
+    #[template_child]
+    pub confirm_password: TemplateChild<Entry>,
+    #[template_child]
+    pub register_button: TemplateChild<Button>,
+    #[template_child]
+    pub login_error: TemplateChild<Label>,
+    #[template_child]
     pub todo_list: TemplateChild<gtk::Box>,
     #[template_child]
     pub author_salutation: TemplateChild<Label>,
     #[template_child]
     pub task_entry: TemplateChild<Entry>,
     #[template_child]
+    pub search_entry: TemplateChild<SearchEntry>,
+    #[template_child]
     pub task_list: TemplateChild<ListView>,
+    #[template_child]
+    pub error_banner: TemplateChild<Revealer>,
+    #[template_child]
+    pub error_label: TemplateChild<Label>,
+    #[template_child]
+    pub undo_banner: TemplateChild<Revealer>,
+    #[template_child]
+    pub undo_label: TemplateChild<Label>,
+    pub undo_timeout: RefCell<Option<glib::SourceId>>,
     pub task_store: RefCell<Option<gio::ListStore>>,
+    pub task_filter: RefCell<Option<CustomFilter>>,
+    pub search_matches: RefCell<Option<HashSet<String>>>,
+    pub task_sorter: RefCell<Option<CustomSorter>>,
+    pub section_sorter: RefCell<Option<CustomSorter>>,
+    pub sort_key: Cell<SortKey>,
+    pub sort_order: Cell<SortOrder>,
+    pub group_by: Cell<Option<GroupBy>>,
+    pub storage: RefCell<Option<Rc<dyn Storage>>>,
+    pub session: RefCell<Option<Session>>,
 }
 
 #[glib::object_subclass]
@@ -55,12 +96,20 @@ impl ObjectImpl for AppWindow {
         let obj = self.obj();
         obj.setup_tasks();
         obj.setup_callbacks();
+        obj.setup_actions();
         obj.setup_factory();
     }
 }
 
 impl WidgetImpl for AppWindow {}
 
-impl WindowImpl for AppWindow {}
+impl WindowImpl for AppWindow {
+    fn close_request(&self) -> glib::Propagation {
+        // Save the tasks before closing the window
+        self.obj().save_tasks();
+
+        self.parent_close_request()
+    }
+}
 
 impl ApplicationWindowImpl for AppWindow {}
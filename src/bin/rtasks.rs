@@ -0,0 +1,654 @@
+//! The command-line interface to the to-do list, which
+//! shares the author and task storage with the GTK app.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chrono::{DateTime, Local, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use csv::WriterBuilder;
+use uuid::Uuid;
+
+use rustic_tasks::app::todo_list::document::read_json;
+use rustic_tasks::app::todo_list::ical::{read_ical, write_ical};
+use rustic_tasks::app::todo_list::task::due_date::parse_natural_date;
+use rustic_tasks::app::todo_list::task::utils::parse_quick_add;
+use rustic_tasks::app::todo_list::todo_txt::{read_todo_txt, write_todo_txt};
+use rustic_tasks::*;
+
+/// Manage the RusticTasks to-do list from the terminal.
+#[derive(Debug, Parser)]
+#[command(name = "rtasks", version)]
+struct Cli {
+    #[command(flatten)]
+    login: LoginArgs,
+
+    #[command(flatten)]
+    store: StoreArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The credentials of the author.
+#[derive(Debug, Args)]
+struct LoginArgs {
+    /// The email address of the author
+    #[arg(long, env = "RTASKS_EMAIL", global = true)]
+    email: Option<String>,
+
+    /// The password of the author, which is asked for when it is not set
+    #[arg(long, env = "RTASKS_PASSWORD", hide_env_values = true, global = true)]
+    password: Option<String>,
+}
+
+/// The storage backend shared with the GTK app.
+#[derive(Debug, Args)]
+struct StoreArgs {
+    /// The CSV file of the authors
+    #[arg(long, default_value = "./data/author.csv", global = true)]
+    authors: PathBuf,
+
+    /// The CSV file of the tasks
+    #[arg(long, default_value = "./data/tasks.csv", global = true)]
+    tasks: PathBuf,
+
+    /// Use the JSON file instead of the CSV files
+    #[arg(long, global = true, conflicts_with_all = ["sqlite", "journal"])]
+    json: Option<PathBuf>,
+
+    /// Use the SQLite database instead of the CSV files
+    #[arg(long, global = true, conflicts_with = "journal")]
+    sqlite: Option<PathBuf>,
+
+    /// Use the journal of the changes instead of the CSV file of the tasks
+    #[arg(long, global = true)]
+    journal: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create an account with the email address and the password
+    Register {
+        /// The name of the author
+        name: String,
+    },
+    /// Add a task written as `Write paper #Thesis !high due:friday @desc "outline first"`
+    Add {
+        /// The task in the quick-add syntax
+        #[arg(required = true)]
+        text: Vec<String>,
+
+        /// Add the task as a subtask of this task
+        #[arg(long)]
+        parent: Option<String>,
+    },
+    /// List the unfinished tasks
+    List {
+        /// Include the completed tasks
+        #[arg(long)]
+        all: bool,
+
+        /// Sort by due_date, priority, created_on, name or status
+        #[arg(long)]
+        sort: Option<SortKey>,
+
+        /// Sort in the descending order
+        #[arg(long)]
+        desc: bool,
+
+        /// Group by status, priority, label or due
+        #[arg(long)]
+        group: Option<GroupBy>,
+    },
+    /// Mark a task as completed
+    Done {
+        /// The id of the task or the start of it
+        id: String,
+    },
+    /// Change the details of a task
+    Edit {
+        /// The id of the task or the start of it
+        id: String,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long)]
+        description: Option<String>,
+
+        /// low, medium or high
+        #[arg(long, value_parser = parse_priority)]
+        priority: Option<Priority>,
+
+        /// A date such as 2024-06-10, "tomorrow 5pm" or "in 3 days"
+        #[arg(long)]
+        due: Option<String>,
+
+        /// The comma-separated labels
+        #[arg(long, value_delimiter = ',')]
+        labels: Option<Vec<String>>,
+
+        #[arg(long, value_enum)]
+        status: Option<StatusArg>,
+    },
+    /// Remove a task along with its subtasks
+    Rm {
+        /// The id of the task or the start of it
+        id: String,
+    },
+    /// List the tasks matching a query such as `status:pending label:Work`
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
+    /// Show the changes made to a task, or to every task in a period
+    History {
+        /// The id of the task or the start of it
+        id: Option<String>,
+
+        /// Only show the changes made since this date, such as 2024-06-10 or yesterday
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show the changes made before this date
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Write a Markdown checklist or an HTML report of the tasks
+    Report {
+        #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+
+        /// Only include the tasks with the status
+        #[arg(long, value_enum)]
+        status: Option<StatusArg>,
+
+        /// Only include the tasks with the label
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Sort by due_date, priority, created_on, name or status
+        #[arg(long, default_value_t = SortKey::DueDate)]
+        sort: SortKey,
+
+        /// Sort in the descending order
+        #[arg(long)]
+        desc: bool,
+
+        /// The file to write, where the standard output is used by default
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Write the tasks to a file or to the standard output
+    Export {
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+
+        /// The file to write, where the standard output is used by default
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Add the tasks from a file or from the standard input
+    Import {
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+
+        /// The file to read, where `-` reads the standard input
+        input: PathBuf,
+    },
+}
+
+/// The file formats of the export and the import.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Csv,
+    /// The versioned JSON document with the profile of the author
+    Json,
+    /// The VTODO components of an iCalendar file
+    Ical,
+    /// The lines of a todo.txt file
+    TodoTxt,
+}
+
+/// The formats of the reports.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// The statuses that can be set with `edit` or picked with `report`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum StatusArg {
+    Pending,
+    InProgress,
+    Complete,
+}
+
+impl From<StatusArg> for Status {
+    fn from(status: StatusArg) -> Self {
+        match status {
+            StatusArg::Pending => Status::Pending,
+            StatusArg::InProgress => Status::InProgress,
+            StatusArg::Complete => Status::Complete,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+/// Logs in, runs the command and saves the changed tasks.
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let storage = open_storage(&cli.store)?;
+
+    if let Command::Register { name } = &cli.command {
+        let (email, password) = ask_credentials(cli.login)?;
+        let author = Author::register(name, &email, &password, storage.as_ref())?;
+        println!("Registered {}", author.get_name());
+
+        return Ok(());
+    }
+
+    let author = login(cli.login, storage.as_ref())?;
+    let mut todo_list = ToDoList::load(author, storage.as_ref())?;
+
+    match cli.command {
+        Command::Register { .. } => {},
+        Command::Add { text, parent } => {
+            let quick_add = parse_quick_add(&text.join(" "), &Local::now());
+            if let Some(token) = quick_add.unparsed.first() {
+                return Err(token.message.clone().into());
+            }
+            if quick_add.name.is_empty() {
+                return Err("The task needs a name.".into());
+            }
+
+            let task = quick_add.build_task(todo_list.get_author())?;
+            let id = *task.get_id();
+            let parent = match parent {
+                Some(parent) => Some(find_id(&todo_list, &parent)?),
+                None => None,
+            };
+            todo_list.execute(TaskCommand::Add { task, parent })?;
+            todo_list.save(storage.as_ref())?;
+
+            println!("Added {}", short_id(&id));
+        },
+        Command::List { all, sort, desc, group } => {
+            let order = if desc { SortOrder::Descending } else { SortOrder::Ascending };
+            let shown = |task: &Task| all || task.get_status() != &Status::Complete;
+
+            match (group, sort) {
+                (Some(group_by), sort) => {
+                    let groups = todo_list.grouped(
+                        group_by,
+                        sort.unwrap_or_default(),
+                        order,
+                        &Local::now(),
+                    );
+                    for (group_key, tasks) in groups {
+                        let tasks: Vec<&Task> = tasks.into_iter().filter(|task| shown(task)).collect();
+                        if tasks.is_empty() {
+                            continue;
+                        }
+
+                        println!("{group_key}");
+                        for task in tasks {
+                            println!("  {}", describe_task(task));
+                        }
+                    }
+                },
+                (None, Some(sort_key)) => {
+                    for task in todo_list.sorted(sort_key, order).into_iter().filter(|task| shown(task)) {
+                        println!("{}", describe_task(task));
+                    }
+                },
+                // Show the subtasks under their parents
+                (None, None) => {
+                    for (depth, task) in todo_list.walk() {
+                        if shown(task) {
+                            println!("{}{}", "  ".repeat(depth), describe_task(task));
+                        }
+                    }
+                },
+            }
+        },
+        Command::Done { id } => {
+            let id = find_id(&todo_list, &id)?;
+            let next_id = todo_list.complete(&id)?;
+            todo_list.save(storage.as_ref())?;
+
+            println!("Completed {}", short_id(&id));
+            if let Some(next_id) = next_id {
+                println!("Added the next occurrence {}", describe_task(todo_list.get(&next_id)?));
+            }
+        },
+        Command::Edit { id, name, description, priority, due, labels, status } => {
+            let id = find_id(&todo_list, &id)?;
+            let due_date = due
+                .map(|due| parse_natural_date(&due, &Local::now()))
+                .transpose()?;
+
+            let edit = TaskEdit {
+                name,
+                description: description.map(Some),
+                priority,
+                due_date: due_date.map(Some),
+                labels: labels.map(|labels| {
+                    labels.iter().map(|label| label.trim().to_string()).collect()
+                }),
+            };
+            todo_list.execute(TaskCommand::Edit(id, edit))?;
+            if let Some(status) = status {
+                todo_list.update_status(&id, status.into())?;
+            }
+            todo_list.save(storage.as_ref())?;
+
+            println!("{}", describe_task(todo_list.get(&id)?));
+        },
+        Command::Rm { id } => {
+            let id = find_id(&todo_list, &id)?;
+            let removed = describe_tree(&todo_list, &id);
+            todo_list.execute(TaskCommand::Remove(id))?;
+            todo_list.save(storage.as_ref())?;
+
+            for line in removed {
+                println!("Removed {line}");
+            }
+        },
+        Command::Search { query } => {
+            for task in todo_list.query(&query.join(" "))? {
+                println!("{}", describe_task(task));
+            }
+        },
+        Command::History { id, since, until } => {
+            let now = Local::now();
+            let start = match since {
+                Some(since) => parse_natural_date(&since, &now)?,
+                None => DateTime::<Utc>::UNIX_EPOCH.with_timezone(&Local),
+            };
+            let end = match until {
+                Some(until) => parse_natural_date(&until, &now)?,
+                None => DateTime::<Utc>::MAX_UTC.with_timezone(&Local),
+            };
+
+            match id {
+                Some(id) => {
+                    let task = todo_list.get(&find_id(&todo_list, &id)?)?;
+                    println!("{}", describe_task(task));
+                    for event in task.get_changes_between(&start, &end) {
+                        println!("  {event}");
+                    }
+                },
+                None => {
+                    for (task, event) in todo_list.get_changes_between(&start, &end) {
+                        println!("{} {}: {event}", short_id(task.get_id()), task.get_name());
+                    }
+                },
+            }
+        },
+        Command::Report { format, status, label, sort, desc, output } => {
+            let options = ReportOptions {
+                status: status.map(Status::from),
+                label,
+                sort_key: sort,
+                sort_order: if desc { SortOrder::Descending } else { SortOrder::Ascending },
+            };
+            let report = match format {
+                ReportFormat::Markdown => todo_list.to_markdown(&options, &Local::now()),
+                ReportFormat::Html => todo_list.to_html(&options, &Local::now()),
+            };
+
+            match output {
+                Some(path) => fs::write(path, report)?,
+                None => print!("{report}"),
+            }
+        },
+        Command::Export { format, output } => {
+            let mut writer: Box<dyn Write> = match output {
+                Some(path) => Box::new(File::create(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+            export_tasks(&todo_list, format, &mut writer)?;
+        },
+        Command::Import { format, input } => {
+            let tasks = import_tasks(&input, format)?;
+            let count = todo_list.import(tasks);
+            todo_list.save(storage.as_ref())?;
+
+            println!("Imported {count} tasks");
+        },
+    }
+
+    Ok(())
+}
+
+/// Opens the storage backend chosen by the arguments.
+fn open_storage(store: &StoreArgs) -> Result<Box<dyn Storage>, RusticError> {
+    if let Some(path) = &store.sqlite {
+        return Ok(Box::new(SqliteStorage::open(path)?));
+    }
+    if let Some(path) = &store.json {
+        return Ok(Box::new(JsonStorage::new(path)));
+    }
+    if let Some(path) = &store.journal {
+        return Ok(Box::new(JournalStorage::new(&store.authors, path)));
+    }
+
+    Ok(Box::new(CsvStorage::new(&store.authors, &store.tasks)))
+}
+
+/// Logs in as the author, asking for the missing credentials.
+fn login(login: LoginArgs, storage: &dyn Storage) -> Result<Author, Box<dyn Error>> {
+    let (email, password) = ask_credentials(login)?;
+
+    match Author::login(&email, &password, storage)? {
+        LoginState::LoggedIn(author) => Ok(author),
+        LoginState::DoesNotExist => Err(format!("There is no account for {email}.").into()),
+        LoginState::InvalidCredentials => Err("The password is incorrect.".into()),
+    }
+}
+
+/// Returns the email address and the password, asking for the missing ones.
+fn ask_credentials(login: LoginArgs) -> Result<(String, String), Box<dyn Error>> {
+    let email = match login.email {
+        Some(email) => email,
+        None => {
+            eprint!("Email: ");
+            let mut email = String::new();
+            io::stdin().read_line(&mut email)?;
+            email.trim().to_string()
+        },
+    };
+    let password = match login.password {
+        Some(password) => password,
+        None => rpassword::prompt_password("Password: ")?,
+    };
+
+    Ok((email, password))
+}
+
+/// Finds the id of the only task whose id starts with the prefix.
+fn find_id(todo_list: &ToDoList, prefix: &str) -> Result<Uuid, Box<dyn Error>> {
+    let prefix = prefix.to_lowercase();
+    let ids: Vec<Uuid> = todo_list
+        .get_tasks()
+        .iter()
+        .map(|task| *task.get_id())
+        .filter(|id| id.to_string().starts_with(&prefix))
+        .collect();
+
+    match ids[..] {
+        [id] => Ok(id),
+        [] => Err(format!("There is no task starting with {prefix}.").into()),
+        _ => Err(format!("There are {} tasks starting with {prefix}.", ids.len()).into()),
+    }
+}
+
+/// Returns the first part of the id, which is usually enough to tell the tasks apart.
+fn short_id(id: &Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+/// Describes the task along with its subtasks in the depth-first order, one per line.
+fn describe_tree(todo_list: &ToDoList, id: &Uuid) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut stack = vec![*id];
+    let mut visited = HashSet::new();
+    while let Some(id) = stack.pop() {
+        // Guard against broken links that form a loop or lead nowhere
+        let Ok(task) = todo_list.get(&id) else {
+            continue;
+        };
+        if !visited.insert(id) {
+            continue;
+        }
+
+        lines.push(describe_task(task));
+        stack.extend(task.get_subtask_ids().iter().rev());
+    }
+
+    lines
+}
+
+/// Describes the task on a single line.
+fn describe_task(task: &Task) -> String {
+    let mark = match task.get_status() {
+        Status::Pending => " ",
+        Status::InProgress => "~",
+        Status::Complete => "x",
+    };
+
+    let mut line = format!("{} [{mark}] {}", short_id(task.get_id()), task.get_name());
+    line.push_str(&format!(" !{}", task.get_priority().to_string().to_lowercase()));
+    if let Some(due_date) = task.get_due_date() {
+        line.push_str(&format!(" due:{}", due_date.format("%Y-%m-%d %H:%M")));
+    }
+    for label in task.get_labels() {
+        line.push_str(&format!(" #{label}"));
+    }
+
+    line
+}
+
+/// Writes the tasks of the to-do list in the format.
+fn export_tasks(
+    todo_list: &ToDoList,
+    format: Format,
+    writer: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    let tasks = todo_list.get_tasks();
+
+    match format {
+        Format::Csv => {
+            let mut csv_writer = WriterBuilder::new().from_writer(writer);
+            for task in tasks {
+                csv_writer.serialize(task)?;
+            }
+            csv_writer.flush()?;
+        },
+        Format::Json => {
+            writeln!(writer, "{}", todo_list.to_json_document().to_json()?)?;
+        },
+        Format::Ical => write!(writer, "{}", write_ical(tasks))?,
+        Format::TodoTxt => write!(writer, "{}", write_todo_txt(tasks))?,
+    }
+
+    Ok(())
+}
+
+/// Reads the tasks in the format from the file, where `-` is the standard input.
+fn import_tasks(input: &Path, format: Format) -> Result<Vec<Task>, Box<dyn Error>> {
+    let contents = if input == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents)?;
+        contents
+    } else {
+        fs::read_to_string(input)?
+    };
+
+    let tasks = match format {
+        Format::Csv => csv::Reader::from_reader(contents.as_bytes())
+            .deserialize()
+            .collect::<Result<Vec<Task>, csv::Error>>()?,
+        Format::Json => read_json(&contents)?,
+        Format::Ical => read_ical(&contents)?,
+        Format::TodoTxt => read_todo_txt(&contents)?,
+    };
+
+    Ok(tasks)
+}
+
+/// Parses the priority from its name or its value.
+fn parse_priority(name: &str) -> Result<Priority, String> {
+    Priority::from_name(name).ok_or_else(|| format!("'{name}' is not low, medium or high"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_tasks_by_id_prefix() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author)?;
+        for name in ["Practice the piano", "Write a paper"] {
+            let task = Task::new(name, None, None, 2, vec![], todo_list.get_author())?;
+            todo_list.add(task);
+        }
+        let id = *todo_list.get_tasks()[0].get_id();
+
+        // The prefix may be in either case
+        assert_eq!(find_id(&todo_list, &short_id(&id)).ok(), Some(id));
+        assert_eq!(find_id(&todo_list, &id.to_string().to_uppercase()).ok(), Some(id));
+
+        let missing = find_id(&todo_list, "xyz").unwrap_err();
+        assert_eq!(missing.to_string(), "There is no task starting with xyz.");
+        let ambiguous = find_id(&todo_list, "").unwrap_err();
+        assert_eq!(ambiguous.to_string(), "There are 2 tasks starting with .");
+
+        Ok(())
+    }
+
+    #[test]
+    fn describe_tasks_on_one_line() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author)?;
+        let task = Task::new(
+            "Write a paper",
+            None,
+            Some("2024-06-10 16:00"),
+            3,
+            vec![ "Thesis", "Work" ],
+            todo_list.get_author(),
+        )?;
+        let id = *task.get_id();
+        todo_list.add(task);
+
+        let line = describe_task(todo_list.get(&id)?);
+        assert_eq!(line, format!("{} [ ] Write a paper !high due:2024-06-10 16:00 #Thesis #Work", short_id(&id)));
+
+        todo_list.update_status(&id, Status::InProgress)?;
+        assert!(describe_task(todo_list.get(&id)?).contains(" [~] "));
+        todo_list.complete(&id)?;
+        assert!(describe_task(todo_list.get(&id)?).contains(" [x] "));
+
+        let task = Task::new("Call the bank", None, None, 1, vec![], todo_list.get_author())?;
+        assert_eq!(describe_task(&task), format!("{} [ ] Call the bank !low", short_id(task.get_id())));
+
+        Ok(())
+    }
+}
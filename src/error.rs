@@ -1,6 +1,7 @@
 use std::{error, fmt, io};
 
 use thiserror::Error;
+use uuid::Uuid;
 
 /// The main error enumeration that handles error propagation.
 #[derive(Debug, Error)]
@@ -23,11 +24,44 @@ pub enum RusticError {
     #[error("RegexError encountered!\n\n{0}")]
     RegexError(#[from] regex::Error),
 
+    #[error("SQLiteError encountered!\n\n{0}")]
+    SQLiteError(#[from] rusqlite::Error),
+
     #[error("AuthorExists encountered!\n")]
     AuthorExists,
 
+    #[error("DependencyCycle encountered!\n\n{1} already depends on {0}")]
+    DependencyCycle(Uuid, Uuid),
+
+    #[error("InvalidDueDate encountered!\n\n{0}")]
+    InvalidDueDate(String),
+
     #[error("InvalidEmail encountered!\n")]
     InvalidEmail,
+
+    #[error("InvalidICalendar encountered!\n\nAt line {0}: {1}")]
+    InvalidICalendar(usize, String),
+
+    #[error("InvalidQuery encountered!\n\nAt position {0}: {1}")]
+    InvalidQuery(usize, String),
+
+    #[error("InvalidRecurrence encountered!\n\n{0}")]
+    InvalidRecurrence(String),
+
+    #[error("InvalidTodoTxt encountered!\n\nAt line {0}: {1}")]
+    InvalidTodoTxt(usize, String),
+
+    #[error("InvalidViewOption encountered!\n\n{0}")]
+    InvalidViewOption(String),
+
+    #[error("TaskBlocked encountered!\n\n{0}")]
+    TaskBlocked(Uuid),
+
+    #[error("TaskNotFound encountered!\n\n{0}")]
+    TaskNotFound(Uuid),
+
+    #[error("UnsupportedSchemaVersion encountered!\n\nThe schema version {0} is not supported")]
+    UnsupportedSchemaVersion(u64),
 }
 
 /// The wrapper to handle argon2 errors.
@@ -1,17 +1,48 @@
 /// The module that handles the functionalities
 /// of the app GUI and the app logic.
 pub mod app;
+#[cfg(feature = "gui")]
 pub use app::App;
 pub use app::logic::{
-    AppWindow,
     Author,
+    AuthorProfile,
+    Comparison,
+    CsvStorage,
+    DueBucket,
+    Filter,
+    GroupBy,
+    GroupKey,
+    Journal,
+    JournalEntry,
+    JournalEvent,
+    JournalStorage,
+    JsonDocument,
+    JsonStorage,
     LoginState,
+    MemoryStorage,
     Priority,
+    Query,
+    Recurrence,
+    ReportOptions,
+    Session,
+    SortKey,
+    SortOrder,
+    SqliteStorage,
     Status,
+    Storage,
     Task,
+    TaskCommand,
+    TaskEdit,
+    TaskEvent,
+    TaskField,
+    ToDoList,
+    UndoStack,
+};
+#[cfg(feature = "gui")]
+pub use app::logic::{
+    AppWindow,
     TaskBox,
     TaskEntry,
-    ToDoList,
 };
 
 /// The module that handles the
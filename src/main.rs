@@ -1,5 +1,6 @@
 use rustic_tasks::*;
 
+#[cfg(feature = "gui")]
 fn main() -> Result<(), RusticError> {
     let exit_code = App::build();
     println!("{:?}", exit_code);
@@ -7,20 +8,65 @@ fn main() -> Result<(), RusticError> {
     Ok(())
 }
 
+// Without the GTK app, point to the command-line interface instead
+#[cfg(not(feature = "gui"))]
+fn main() -> Result<(), RusticError> {
+    eprintln!("RusticTasks was built without the `gui` feature, so use `rtasks` instead.");
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::path::Path;
+    use std::{collections::HashMap, path::Path};
     
+    use chrono::{Duration, Local, TimeZone};
     use regex::Regex;
+    use uuid::Uuid;
     
     use app::todo_list::author::utils::{
         is_valid_email,
         verify_password,
     };
-    use app::todo_list::task::utils::parse_due_date;
+    use app::todo_list::document::read_json;
+    use app::todo_list::ical::read_ical;
+    use app::todo_list::task::due_date::parse_natural_date;
+    use app::todo_list::task::utils::{parse_due_date, parse_quick_add};
+    use app::todo_list::todo_txt::read_todo_txt;
+    use app::todo_list::utils::upgrade_task_owners;
 
     use super::*;
 
+    /// Checks that the imported tasks match the tasks in order, under ids
+    /// of their own with the links between them following the new ids.
+    /// The dates and the history are left to the formats to check, which
+    /// keep them to different precisions.
+    fn assert_same_tasks(tasks: &[Task], imported: &[Task]) {
+        assert_eq!(imported.len(), tasks.len());
+        let ids: HashMap<&Uuid, Uuid> = tasks
+            .iter()
+            .map(Task::get_id)
+            .zip(imported.iter().map(|task| *task.get_id()))
+            .collect();
+        let new_ids = |old_ids: &[Uuid]| -> Vec<Uuid> {
+            old_ids.iter().map(|id| ids[id]).collect()
+        };
+
+        for (task, other) in tasks.iter().zip(imported) {
+            assert_ne!(other.get_id(), task.get_id());
+            assert_eq!(other.get_name(), task.get_name());
+            assert_eq!(other.get_description(), task.get_description());
+            assert_eq!(other.get_due_date(), task.get_due_date());
+            assert_eq!(other.get_priority(), task.get_priority());
+            assert_eq!(other.get_status(), task.get_status());
+            assert_eq!(other.get_labels(), task.get_labels());
+            assert_eq!(other.get_recurrence(), task.get_recurrence());
+            assert_eq!(other.get_parent(), task.get_parent().map(|id| &ids[id]));
+            assert_eq!(other.get_subtask_ids(), &new_ids(task.get_subtask_ids()));
+            assert_eq!(other.get_blockers(), &new_ids(task.get_blockers()));
+        }
+    }
+
     #[test]
     fn print_dummy_todo_list_and_write_to_csv() -> Result<(), RusticError> {
         let author = Author::build(
@@ -61,8 +107,10 @@ mod tests {
         
         println!("{:#?}", dummy_list);
         
-        let file_path = Path::new("./data/tasks_test.csv");
-        if let Err(e) = dummy_list.write_to_csv(file_path) {
+        // Keep the fixture in ./data as it is for the other tests
+        let file_path = std::env::temp_dir().join("rustic_tasks_dummy_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        if let Err(e) = dummy_list.write_to_csv(&file_path) {
             eprintln!("{e}");
         } else {
             println!("Written tasks to: {}", file_path.display());
@@ -100,6 +148,488 @@ mod tests {
         assert!(parse_due_date("2024-06-10T16:00:00+05:30").is_ok());
     }
     
+    #[test]
+    fn parse_natural_due_dates() -> Result<(), RusticError> {
+        // Wednesday, the 12th of June 2024
+        let now = Local.with_ymd_and_hms(2024, 6, 12, 9, 30, 0).unwrap();
+        let at = |y, m, d, h, min, s| Local.with_ymd_and_hms(y, m, d, h, min, s).unwrap();
+
+        for (input, expected) in [
+            ("2024-06-10", at(2024, 6, 10, 23, 59, 59)),
+            ("2024-06-10T16:00", at(2024, 6, 10, 16, 0, 0)),
+            ("2024-06-10 16:00:30", at(2024, 6, 10, 16, 0, 30)),
+            ("2024-06-10 5pm", at(2024, 6, 10, 17, 0, 0)),
+            ("today", at(2024, 6, 12, 23, 59, 59)),
+            ("tomorrow 5pm", at(2024, 6, 13, 17, 0, 0)),
+            ("Tomorrow at 12am", at(2024, 6, 13, 0, 0, 0)),
+            ("next friday", at(2024, 6, 14, 23, 59, 59)),
+            ("wed 9:15am", at(2024, 6, 19, 9, 15, 0)),
+            ("in 3 days", at(2024, 6, 15, 23, 59, 59)),
+            ("in a week noon", at(2024, 6, 19, 12, 0, 0)),
+            ("in 2 hours", at(2024, 6, 12, 11, 30, 0)),
+            ("in 1 month", at(2024, 7, 12, 23, 59, 59)),
+            ("end of month", at(2024, 6, 30, 23, 59, 59)),
+            ("end of the week 18:00", at(2024, 6, 16, 18, 0, 0)),
+            ("end of year", at(2024, 12, 31, 23, 59, 59)),
+            ("17:30", at(2024, 6, 12, 17, 30, 0)),
+        ] {
+            assert_eq!(parse_natural_date(input, &now)?, expected, "{input}");
+        }
+
+        // The timestamps with an offset should keep their instant
+        let due_date = parse_natural_date("2024-06-10T16:00:00+05:30", &now)?;
+        assert_eq!(due_date.to_rfc3339(), parse_due_date("2024-06-10T16:00:00+05:30")?.to_rfc3339());
+
+        for input in [
+            "",
+            "someday",
+            "in many days",
+            "in 3 fortnights",
+            "13pm",
+            "in 2 hours 5pm",
+            "in 4000000000 days",
+            "in 400000000 years",
+            "in 4000000000 hours",
+        ] {
+            assert!(matches!(
+                parse_natural_date(input, &now),
+                Err(RusticError::InvalidDueDate(_))
+            ), "{input}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_quick_add_syntax() -> Result<(), RusticError> {
+        // Wednesday, the 12th of June 2024
+        let now = Local.with_ymd_and_hms(2024, 6, 12, 9, 30, 0).unwrap();
+
+        let input = "Write paper #Thesis !high due:friday @desc \"outline first\"";
+        let quick_add = parse_quick_add(input, &now);
+        assert_eq!(quick_add.name, "Write paper");
+        assert_eq!(quick_add.labels, vec!["Thesis"]);
+        assert_eq!(quick_add.priority, Some(Priority::High));
+        assert_eq!(quick_add.due_date, Some(Local.with_ymd_and_hms(2024, 6, 14, 23, 59, 59).unwrap()));
+        assert_eq!(quick_add.description.as_deref(), Some("outline first"));
+        assert!(quick_add.unparsed.is_empty());
+
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let task = quick_add.build_task(&author)?;
+        assert_eq!(task.get_name(), "Write paper");
+        assert_eq!(task.get_priority(), &Priority::High);
+        assert_eq!(task.get_due_date(), quick_add.due_date.as_ref());
+
+        let quick_add = parse_quick_add("Call \"Mom\" due:\"tomorrow 5pm\" #home #family", &now);
+        assert_eq!(quick_add.name, "Call Mom");
+        assert_eq!(quick_add.labels, vec!["home", "family"]);
+        assert_eq!(quick_add.due_date, Some(Local.with_ymd_and_hms(2024, 6, 13, 17, 0, 0).unwrap()));
+
+        // The tokens that cannot be parsed should be reported with their place
+        let input = "Fix bug !urgent due:someday # @desc";
+        let quick_add = parse_quick_add(input, &now);
+        assert_eq!(quick_add.name, "Fix bug");
+        let unparsed: Vec<&str> = quick_add.unparsed
+            .iter()
+            .map(|token| &input[token.range.clone()])
+            .collect();
+        assert_eq!(unparsed, vec!["!urgent", "due:someday", "#", "@desc"]);
+
+        let input = "Buy milk \"unclosed";
+        let quick_add = parse_quick_add(input, &now);
+        assert_eq!(quick_add.name, "Buy milk");
+        assert_eq!(&input[quick_add.unparsed[0].range.clone()], "\"unclosed");
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_tasks_through_ical() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let report = Task::new(
+            "Write the report; part 1, draft",
+            Some("Collect the numbers from the café\nand the C:\\exports folder, then \
+                summarise them for the quarterly review meeting"),
+            Some("2024-06-10T16:00:00+05:30"),
+            3,
+            vec!["work", "q2,review"],
+            &author,
+        )?;
+        let report_id = *report.get_id();
+        todo_list.add(report);
+        todo_list.update_status(&report_id, Status::InProgress)?;
+
+        let numbers = Task::new("Collect the numbers", None, Some("2024-06-07"), 1, vec![], &author)?;
+        let numbers_id = *numbers.get_id();
+        todo_list.add_subtask(&report_id, numbers)?;
+        todo_list.complete(&numbers_id)?;
+
+        let mut review = Task::new("Review the report", None, None, 2, vec!["work"], &author)?;
+        review.set_recurrence(Some(Recurrence::Daily { interval: 2 }));
+        let review_id = *review.get_id();
+        todo_list.add(review);
+        todo_list.add_blocker(&review_id, &report_id)?;
+
+        let file_path = std::env::temp_dir().join("rustic_tasks_ical_test.ics");
+        todo_list.write_to_ical(&file_path)?;
+
+        // The lines should be folded and the text escaped
+        let calendar = std::fs::read_to_string(&file_path)?;
+        assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+        assert!(calendar.contains("SUMMARY:Write the report\\; part 1\\, draft"));
+        assert!(calendar.contains("CATEGORIES:work,q2\\,review"));
+
+        let jane = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut imported = ToDoList::build(jane)?;
+        assert_eq!(imported.import_from(&file_path, read_ical)?, 3);
+        assert_eq!(imported.import_from(&file_path, read_ical)?, 0);
+
+        // Importing the exported list back into itself should add nothing
+        let count = todo_list.get_tasks().len();
+        assert_eq!(todo_list.import_from(&file_path, read_ical)?, 0);
+        assert_eq!(todo_list.get_tasks().len(), count);
+        std::fs::remove_file(&file_path)?;
+
+        // Every field should survive, with the other dates to the second
+        assert_same_tasks(todo_list.get_tasks(), imported.get_tasks());
+        for (task, other) in todo_list.get_tasks().iter().zip(imported.get_tasks()) {
+            assert_eq!(
+                other.get_completed_on().map(|date| date.timestamp()),
+                task.get_completed_on().map(|date| date.timestamp()),
+            );
+            assert_eq!(other.get_created_on().timestamp(), task.get_created_on().timestamp());
+        }
+
+        // The calendars of other tools should be read as well
+        let calendar = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\n\
+            SUMMARY:Standup\r\nEND:VEVENT\r\nBEGIN:VTODO\r\nUID:abc@example.com\r\n\
+            SUMMARY:Renew the pass\r\n port\r\nDUE;VALUE=DATE:20240701\r\nPRIORITY:0\r\n\
+            STATUS:CANCELLED\r\nBEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\n\
+            END:VTODO\r\nBEGIN:VTODO\r\nUID:def@example.com\r\nSUMMARY:Book the photo\r\n\
+            RELATED-TO:abc@example.com\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let tasks = read_ical(calendar)?;
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].get_name(), "Renew the passport");
+        assert_eq!(tasks[0].get_description(), None);
+        assert_eq!(tasks[0].get_priority(), &Priority::Medium);
+        assert_eq!(tasks[0].get_status(), &Status::Complete);
+        let due_date = Local.with_ymd_and_hms(2024, 7, 1, 23, 59, 59).unwrap();
+        assert_eq!(tasks[0].get_due_date(), Some(&due_date));
+        assert_eq!(tasks[1].get_parent(), Some(tasks[0].get_id()));
+
+        assert!(matches!(
+            read_ical("BEGIN:VCALENDAR\nBEGIN:VTODO\nPRIORITY:high\nEND:VTODO\nEND:VCALENDAR"),
+            Err(RusticError::InvalidICalendar(3, _))
+        ));
+        assert!(matches!(
+            read_ical("BEGIN:VCALENDAR\nBEGIN:VTODO\nEND:VCALENDAR"),
+            Err(RusticError::InvalidICalendar(3, _))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_tasks_through_todo_txt() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let mut call = Task::new(
+            "Call the bank",
+            None,
+            Some("2024-06-12T16:30"),
+            3,
+            vec!["Finance", "@phone", "Q2 budget"],
+            &author,
+        )?;
+        call.set_recurrence(Some(Recurrence::Daily { interval: 2 }));
+        let call_id = *call.get_id();
+        todo_list.add(call);
+        todo_list.update_status(&call_id, Status::InProgress)?;
+
+        let statement = Task::new("Print the statement", None, Some("2024-06-11"), 1, vec![], &author)?;
+        let statement_id = *statement.get_id();
+        todo_list.add_subtask(&call_id, statement)?;
+        let number = Task::new("Find the account number", None, None, 2, vec![], &author)?;
+        todo_list.add_subtask(&call_id, number)?;
+        todo_list.complete(&statement_id)?;
+
+        let transfer = Task::new("Make the transfer", None, None, 2, vec!["Finance"], &author)?;
+        let transfer_id = *transfer.get_id();
+        todo_list.add(transfer);
+        todo_list.add_blocker(&transfer_id, &call_id)?;
+
+        // The names that look like the todo.txt syntax should stay names
+        let raise = Task::new(
+            "Ask @boss for a +1 due:soon at 10:30, 50% or 100%25",
+            Some("Bring the numbers\nand the +1 from @hr"),
+            None,
+            2,
+            vec![],
+            &author,
+        )?;
+        todo_list.add(raise);
+
+        let file_path = std::env::temp_dir().join("rustic_tasks_todo_test.txt");
+        todo_list.write_to_todo_txt(&file_path)?;
+
+        let lines = std::fs::read_to_string(&file_path)?;
+        let lines: Vec<&str> = lines.lines().collect();
+        let today = Local::now().format("%Y-%m-%d");
+        assert!(lines[0].starts_with(&format!("(A) {today} Call the bank +Finance @phone +Q2%20budget")));
+        assert!(lines[0].contains(" due:2024-06-12T16:30 status:in-progress rrule:FREQ=DAILY;INTERVAL=2 "));
+        assert!(lines[1].starts_with(&format!("x {today} {today} Print the statement due:2024-06-11 pri:C")));
+        assert!(lines[3].contains(&format!("blocked:{call_id}")));
+        assert!(lines[4].starts_with(&format!("(B) {today} Ask %40boss for a %2B1 due%3Asoon at 10:30, 50%25 or 100%2525 ")));
+        assert!(lines[4].contains(" desc:Bring%20the%20numbers%0Aand%20the%20+1%20from%20@hr "));
+
+        let jane = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut imported = ToDoList::build(jane)?;
+        assert_eq!(imported.import_from(&file_path, read_todo_txt)?, 5);
+        assert_eq!(imported.import_from(&file_path, read_todo_txt)?, 0);
+
+        // Importing the exported list back into itself should add nothing
+        let count = todo_list.get_tasks().len();
+        assert_eq!(todo_list.import_from(&file_path, read_todo_txt)?, 0);
+        assert_eq!(todo_list.get_tasks().len(), count);
+        std::fs::remove_file(&file_path)?;
+
+        // Every field should survive, with the other dates down to the day
+        assert_same_tasks(todo_list.get_tasks(), imported.get_tasks());
+        let day = |date: &chrono::DateTime<Local>| date.date_naive();
+        for (task, other) in todo_list.get_tasks().iter().zip(imported.get_tasks()) {
+            assert_eq!(other.get_completed_on().map(day), task.get_completed_on().map(day));
+            assert_eq!(day(other.get_created_on()), day(task.get_created_on()));
+        }
+
+        // The lists kept by other todo.txt tools should be read as well
+        let tasks = read_todo_txt(
+            "(A) Thank Mom for the meatballs @phone\n\n\
+            x 2024-03-02 2024-03-01 Review the +TodoTxt spec t:2024-02-28\n\
+            (D) 2024-05-20 Post the signed forms +Garden due:2024-06-01\n",
+        )?;
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].get_priority(), &Priority::High);
+        assert_eq!(tasks[0].get_labels(), &vec!["@phone"]);
+        assert_eq!(tasks[1].get_status(), &Status::Complete);
+        assert_eq!(tasks[1].get_name(), "Review the spec t:2024-02-28");
+        let completed_on = Local.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+        assert_eq!(tasks[1].get_completed_on(), Some(&completed_on));
+        assert_eq!(tasks[2].get_priority(), &Priority::Low);
+        let due_date = Local.with_ymd_and_hms(2024, 6, 1, 23, 59, 59).unwrap();
+        assert_eq!(tasks[2].get_due_date(), Some(&due_date));
+
+        assert!(matches!(
+            read_todo_txt("(A) Water the plants\n(B) Feed the cat due:whenever"),
+            Err(RusticError::InvalidTodoTxt(2, _))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_markdown_and_html_reports() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let now = Local.with_ymd_and_hms(2024, 6, 12, 9, 30, 0).unwrap();
+
+        let invoice = Task::new(
+            "Send the <invoice>",
+            Some("Attach the *final* numbers"),
+            Some("2024-06-10T16:00"),
+            3,
+            vec!["work"],
+            &author,
+        )?;
+        todo_list.add(invoice);
+
+        let slides = Task::new("Draft the slides", None, Some("2024-06-14"), 2, vec!["Work"], &author)?;
+        let slides_id = *slides.get_id();
+        todo_list.add(slides);
+        todo_list.update_status(&slides_id, Status::InProgress)?;
+
+        let plants = Task::new("Water the plants", None, Some("2024-06-01"), 1, vec!["home"], &author)?;
+        let plants_id = *plants.get_id();
+        todo_list.add(plants);
+        todo_list.complete(&plants_id)?;
+
+        // The Markdown checklist should be sorted by the due date and mark the overdue tasks
+        let options = ReportOptions { sort_key: SortKey::DueDate, ..ReportOptions::default() };
+        let markdown = todo_list.to_markdown(&options, &now);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[0], "# Tasks of John");
+        assert_eq!(lines[2], "- [x] Water the plants — Low priority, due 2024-06-01 23:59, `home`");
+        assert_eq!(
+            lines[3],
+            "- [ ] Send the \\<invoice\\> — High priority, due 2024-06-10 16:00, **overdue**, `work`",
+        );
+        assert_eq!(lines[4], "  Attach the \\*final\\* numbers");
+        assert_eq!(lines[5], "- [ ] Draft the slides — Medium priority, in progress, due 2024-06-14 23:59, `Work`");
+
+        // The filters should pick the tasks and the priority should order them
+        let options = ReportOptions {
+            label: Some("work".to_string()),
+            sort_key: SortKey::Priority,
+            sort_order: SortOrder::Descending,
+            ..ReportOptions::default()
+        };
+        let names: Vec<&str> = todo_list.report_tasks(&options).iter().map(|task| task.get_name()).collect();
+        assert_eq!(names, vec!["Send the <invoice>", "Draft the slides"]);
+        let options = ReportOptions { status: Some(Status::Complete), ..ReportOptions::default() };
+        assert_eq!(todo_list.report_tasks(&options).len(), 1);
+
+        // The HTML report should group the tasks by the status and highlight the overdue ones
+        let html = todo_list.to_html(&ReportOptions::default(), &now);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<li class=\"task overdue\">\n<span class=\"name\">Send the &lt;invoice&gt;</span>"));
+        assert!(html.contains("<li class=\"task complete\">\n<span class=\"name\">Water the plants</span>"));
+        let sections: Vec<usize> = ["<h2>Pending (1)</h2>", "<h2>In Progress (1)</h2>", "<h2>Complete (1)</h2>"]
+            .iter()
+            .map(|heading| html.find(heading).unwrap_or(usize::MAX))
+            .collect();
+        assert!(sections.windows(2).all(|pair| pair[0] < pair[1] && pair[1] != usize::MAX));
+        assert!(html.trim_end().ends_with("</html>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn back_up_to_versioned_json() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let mut report = Task::new(
+            "Write the report",
+            Some("Line one\nLine two"),
+            Some("2024-06-10T16:00:00.250+05:30"),
+            3,
+            vec!["work", "q2,review"],
+            &author,
+        )?;
+        report.set_recurrence(Some(Recurrence::Monthly { interval: 1, day: 10 }));
+        let report_id = *report.get_id();
+        todo_list.add(report);
+
+        let numbers = Task::new("Collect the numbers", None, None, 1, vec![], &author)?;
+        let numbers_id = *numbers.get_id();
+        todo_list.add_subtask(&report_id, numbers)?;
+        todo_list.add_blocker(&report_id, &numbers_id)?;
+        todo_list.complete(&numbers_id)?;
+
+        let file_path = std::env::temp_dir().join("rustic_tasks_backup_test.json");
+        todo_list.write_to_json(&file_path)?;
+
+        // The document should carry the profile without the password hash
+        let contents = std::fs::read_to_string(&file_path)?;
+        let value: serde_json::Value = serde_json::from_str(&contents)?;
+        assert_eq!(value["schema_version"], 2);
+        assert_eq!(value["author"]["email"], "john@example.com");
+        assert!(value["author"].get("password").is_none());
+        assert!(!contents.contains(author.get_password()));
+        assert_eq!(value["tasks"][0]["labels"][1], "q2,review");
+
+        let jane = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut imported = ToDoList::build(jane.clone())?;
+        assert_eq!(imported.import_from(&file_path, read_json)?, 3);
+        assert_eq!(imported.import_from(&file_path, read_json)?, 0);
+        std::fs::remove_file(&file_path)?;
+
+        // Every field should survive exactly
+        assert_same_tasks(todo_list.get_tasks(), imported.get_tasks());
+        for (task, other) in todo_list.get_tasks().iter().zip(imported.get_tasks()) {
+            assert_eq!(other.get_created_on(), task.get_created_on());
+            assert_eq!(other.get_completed_on(), task.get_completed_on());
+            assert_eq!(other.get_history(), task.get_history());
+            assert!(other.is_created_by(&jane));
+        }
+
+        let document = JsonDocument::from_json(&contents)?;
+        assert_eq!(document.get_author(), &AuthorProfile::from(&author));
+        assert!(document.into_tasks()[0].is_created_by(&author));
+
+        // The documents of version 1 should be upgraded without any history
+        let mut older = value.clone();
+        older["schema_version"] = serde_json::Value::from(1);
+        for task in older["tasks"].as_array_mut().unwrap() {
+            task.as_object_mut().unwrap().remove("history");
+        }
+        let document = JsonDocument::from_json(&older.to_string())?;
+        assert_eq!(document.get_schema_version(), 2);
+        assert!(document.into_tasks().iter().all(|task| task.get_history().is_empty()));
+
+        // The unknown versions should be rejected
+        let newer = contents.replacen("\"schema_version\": 2", "\"schema_version\": 3", 1);
+        assert!(matches!(
+            JsonDocument::from_json(&newer),
+            Err(RusticError::UnsupportedSchemaVersion(3))
+        ));
+        assert!(matches!(
+            JsonDocument::from_json("{\"tasks\": []}"),
+            Err(RusticError::JSONError(_))
+        ));
+        assert!(matches!(JsonDocument::from_json("[1]"), Err(RusticError::JSONError(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn import_tasks_from_another_author() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let other = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let mut task = Task::new("Draft the agenda", None, None, 2, vec!["work"], &other)?;
+        task.rename("Draft the meeting agenda");
+        let id = *task.get_id();
+
+        // The tasks should be taken over by the author only once, under a new id
+        assert_eq!(todo_list.import(vec![task.clone(), task.clone()]), 1);
+        assert_eq!(todo_list.import(vec![task.clone()]), 0);
+        let imported = &todo_list.get_tasks()[0];
+        assert_ne!(imported.get_id(), &id);
+        assert_eq!(imported.get_name(), "Draft the meeting agenda");
+        assert!(imported.is_created_by(&author));
+
+        // The links between the imported tasks should follow the new ids
+        let mut other_list = ToDoList::build(other.clone())?;
+        other_list.add(task);
+        let subtask = Task::new("Book the room", None, None, 2, vec![], &other)?;
+        let subtask_id = *subtask.get_id();
+        other_list.add_subtask(&id, subtask)?;
+        let blocked = Task::new("Send the invites", None, None, 2, vec![], &other)?;
+        let blocked_id = *blocked.get_id();
+        other_list.add(blocked);
+        other_list.add_blocker(&blocked_id, &subtask_id)?;
+
+        let mut todo_list = ToDoList::build(author.clone())?;
+        assert_eq!(todo_list.import(other_list.get_tasks().clone()), 3);
+        let [agenda, room, invites] = &todo_list.get_tasks()[..] else {
+            panic!("Every task should be imported");
+        };
+        assert_eq!(agenda.get_subtask_ids(), &vec![*room.get_id()]);
+        assert_eq!(room.get_parent(), Some(agenda.get_id()));
+        assert_eq!(invites.get_blockers(), &vec![*room.get_id()]);
+
+        // Both lists should survive a shared storage
+        let storage = SqliteStorage::open_in_memory()?;
+        let authors_path = std::env::temp_dir().join("rustic_tasks_import_authors.csv");
+        let tasks_path = std::env::temp_dir().join("rustic_tasks_import_tasks.csv");
+        let _ = std::fs::remove_file(&authors_path);
+        let _ = std::fs::remove_file(&tasks_path);
+        let csv_storage = CsvStorage::new(&authors_path, &tasks_path);
+        for storage in [&storage as &dyn Storage, &csv_storage] {
+            other_list.save(storage)?;
+            todo_list.save(storage)?;
+            assert_eq!(ToDoList::load(other.clone(), storage)?.get_tasks().len(), 3);
+            assert_eq!(ToDoList::load(author.clone(), storage)?.get_tasks().len(), 3);
+        }
+        std::fs::remove_file(&tasks_path)?;
+
+        Ok(())
+    }
+
     #[test]
     fn validate_author() -> Result<(), RusticError> {
         let dummy_author = Author::build(
@@ -151,6 +681,993 @@ mod tests {
     }
 
     #[test]
+    fn read_tasks_back_from_csv() -> Result<(), RusticError> {
+        let author = Author::build(
+            "John",
+            "john@example.com",
+            "password123",
+        )?;
+
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let task = Task::new(
+            "Write a paper",
+            Some("Start with the outline"),
+            Some("2024-06-10T16:00:00+05:30"),
+            3,
+            vec![ "Thesis", "Work" ],
+            todo_list.get_author(),
+        )?;
+        let id = *task.get_id();
+        todo_list.add(task);
+        todo_list.update_status(&id, Status::InProgress)?;
+        todo_list.get_mut(&id)?.mark_completed();
+
+        let file_path = std::env::temp_dir().join("rustic_tasks_read_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        todo_list.write_to_csv(&file_path)?;
+
+        let read_list = ToDoList::read_from_csv(todo_list.get_author().clone(), &file_path)?;
+
+        let stranger = Author::build("Jane", "jane@example.com", "password123")?;
+        let empty_list = ToDoList::read_from_csv(stranger, &file_path)?;
+        assert!(empty_list.get_tasks().is_empty());
+
+        // The tasks are kept by the id of the author rather than the name
+        let namesake = Author::build("John", "john@example.com", "password123")?;
+        let empty_list = ToDoList::read_from_csv(namesake, &file_path)?;
+        std::fs::remove_file(&file_path)?;
+        assert!(empty_list.get_tasks().is_empty());
+
+        // The rows saved by the name of the author should be upgraded like the storage does
+        let legacy_list = ToDoList::read_from_csv(author.clone(), Path::new("./data/tasks_test.csv"))?;
+        assert!(!legacy_list.get_tasks().is_empty());
+        assert!(legacy_list.get_tasks().iter().all(|task| task.is_created_by(&author)));
+
+        let (written, read) = (&todo_list.get_tasks()[0], &read_list.get_tasks()[0]);
+        assert_eq!(read_list.get_tasks().len(), 1);
+        assert_eq!(read.get_id(), written.get_id());
+        assert_eq!(read.get_description(), Some("Start with the outline"));
+        assert_eq!(read.get_labels(), &vec![ "Thesis".to_string(), "Work".to_string() ]);
+        assert!(matches!(read.get_priority(), Priority::High));
+        assert!(matches!(read.get_status(), Status::InProgress));
+        assert_eq!(read.get_due_date(), written.get_due_date());
+        assert_eq!(read.get_completed_on(), written.get_completed_on());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_tasks_in_csv() -> Result<(), RusticError> {
+        let file_path = std::env::temp_dir().join("rustic_tasks_save_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+
+        let jane = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut jane_list = ToDoList::build(jane.clone())?;
+        let jane_task = Task::new("Water the plants", None, None, 1, vec![], jane_list.get_author())?;
+        jane_list.add(jane_task);
+        jane_list.save_to_csv(&file_path)?;
+
+        let john = Author::build("John", "john@example.com", "password123")?;
+        let mut john_list = ToDoList::build(john.clone())?;
+        let john_task = Task::new("Practice the piano", None, None, 3, vec![ "Music" ], john_list.get_author())?;
+        john_list.add(john_task);
+
+        // Saving twice should not duplicate the rows
+        john_list.save_to_csv(&file_path)?;
+        john_list.save_to_csv(&file_path)?;
+        assert_eq!(ToDoList::read_from_csv(john.clone(), &file_path)?.get_tasks().len(), 1);
+
+        // Tasks missing from the list should be dropped
+        let mut john_list = ToDoList::build(john.clone())?;
+        let new_task = Task::new("Write a paper", None, None, 1, vec![ "Thesis" ], john_list.get_author())?;
+        let new_id = *new_task.get_id();
+        john_list.add(new_task);
+        john_list.save_to_csv(&file_path)?;
+
+        let john_list = ToDoList::read_from_csv(john, &file_path)?;
+        assert_eq!(john_list.get_tasks().len(), 1);
+        assert_eq!(john_list.get_tasks()[0].get_id(), &new_id);
+
+        // Rows owned by other authors should be kept, even with the same name
+        let namesake = Author::build("Jane", "jane@example.com", "password123")?;
+        let namesake_list = ToDoList::build(namesake)?;
+        namesake_list.save_to_csv(&file_path)?;
+        assert_eq!(ToDoList::read_from_csv(jane, &file_path)?.get_tasks().len(), 1);
+
+        std::fs::remove_file(&file_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn swap_storage_backends() -> Result<(), RusticError> {
+        fn check_storage(storage: &dyn Storage) -> Result<(), RusticError> {
+            let author = Author::build("John", "john@example.com", "password123")?;
+            author.save(storage)?;
+            assert!(matches!(author.save(storage), Err(RusticError::AuthorExists)));
+
+            assert_eq!(
+                Author::login("john@example.com", "password123", storage)?,
+                LoginState::LoggedIn(author.clone()),
+            );
+            assert_eq!(
+                Author::login("john@example.com", "wrong", storage)?,
+                LoginState::InvalidCredentials,
+            );
+            assert_eq!(
+                Author::login("jane@example.com", "password123", storage)?,
+                LoginState::DoesNotExist,
+            );
+
+            let mut todo_list = ToDoList::load(author, storage)?;
+            let task = Task::new("Practice the piano", None, None, 3, vec![ "Music" ], todo_list.get_author())?;
+            todo_list.add(task);
+            todo_list.save(storage)?;
+            todo_list.save(storage)?;
+
+            let author = storage.find_author("john@example.com")?.expect("Author was saved");
+            let loaded_list = ToDoList::load(author, storage)?;
+            assert_eq!(loaded_list.get_tasks().len(), 1);
+            assert_eq!(loaded_list.get_tasks()[0].get_id(), todo_list.get_tasks()[0].get_id());
+
+            // Another author with the same name should not share the tasks
+            let namesake = Author::build("John", "john.smith@example.com", "password123")?;
+            namesake.save(storage)?;
+            let mut other_list = ToDoList::load(namesake.clone(), storage)?;
+            assert!(other_list.get_tasks().is_empty());
+            other_list.add(Task::new("Walk the dog", None, None, 2, vec![], &namesake)?);
+            other_list.save(storage)?;
+            assert_eq!(ToDoList::load(loaded_list.get_author().clone(), storage)?.get_tasks().len(), 1);
+            assert_eq!(ToDoList::load(namesake, storage)?.get_tasks().len(), 1);
+
+            Ok(())
+        }
+
+        check_storage(&MemoryStorage::new())?;
+
+        let json_path = std::env::temp_dir().join("rustic_tasks_storage_test.json");
+        let _ = std::fs::remove_file(&json_path);
+        check_storage(&JsonStorage::new(&json_path))?;
+        std::fs::remove_file(&json_path)?;
+
+        let authors_path = std::env::temp_dir().join("rustic_tasks_storage_authors.csv");
+        let tasks_path = std::env::temp_dir().join("rustic_tasks_storage_tasks.csv");
+        let _ = std::fs::remove_file(&authors_path);
+        let _ = std::fs::remove_file(&tasks_path);
+        check_storage(&CsvStorage::new(&authors_path, &tasks_path))?;
+        std::fs::remove_file(&authors_path)?;
+        std::fs::remove_file(&tasks_path)?;
+
+        // The older files keep the tasks by the name of the author,
+        // which should go to the earliest author with the name
+        let storage = CsvStorage::new(&authors_path, &tasks_path);
+        std::fs::copy("./data/tasks_test.csv", &tasks_path)?;
+        let john = Author::build("John", "john@example.com", "password123")?;
+        let namesake = Author::build("John", "john.smith@example.com", "password123")?;
+        john.save(&storage)?;
+        namesake.save(&storage)?;
+        assert!(ToDoList::load(namesake, &storage)?.get_tasks().is_empty());
+        let todo_list = ToDoList::load(john.clone(), &storage)?;
+        assert!(!todo_list.get_tasks().is_empty());
+        assert!(std::fs::read_to_string(&tasks_path)?.contains(&john.get_id().to_string()));
+        assert_eq!(ToDoList::read_from_csv(john, &tasks_path)?.get_tasks().len(), todo_list.get_tasks().len());
+        std::fs::remove_file(&authors_path)?;
+        std::fs::remove_file(&tasks_path)?;
+
+        check_storage(&SqliteStorage::open_in_memory()?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_and_import_sqlite_storage() -> Result<(), RusticError> {
+        let storage = SqliteStorage::open_in_memory()?;
+        assert_eq!(storage.schema_version()?, 8);
+
+        let (authors, tasks) = storage.import_csv(
+            Path::new("./data/author_test.csv"),
+            Path::new("./data/tasks_test.csv"),
+        )?;
+        assert!(authors >= 1 && tasks >= 1);
+        assert_eq!(storage.import_csv(
+            Path::new("./data/author_test.csv"),
+            Path::new("./data/tasks_test.csv"),
+        )?, (0, 0));
+
+        let author = storage.find_author("john@example.com")?.expect("Author was imported");
+        let mut todo_list = ToDoList::load(author.clone(), &storage)?;
+        let task = Task::new(
+            "Write a paper",
+            None,
+            Some("2024-06-10T16:00:00+05:30"),
+            1,
+            vec![ "Thesis" ],
+            todo_list.get_author(),
+        )?;
+        let id = *task.get_id();
+        todo_list.add(task);
+        todo_list.update_status(&id, Status::InProgress)?;
+        todo_list.save(&storage)?;
+
+        // The SQL queries should match filtering the loaded tasks
+        let deadline = parse_due_date("2024-07-01T00:00:00+05:30")?;
+        let due_tasks = storage.query_tasks(todo_list.get_author(), None, Some(&deadline))?;
+        let expected = todo_list.get_tasks()
+            .iter()
+            .filter(|task| task.get_due_date().is_some_and(|due| due < &deadline))
+            .count();
+        assert_eq!(due_tasks.len(), expected);
+        assert!(due_tasks.iter().any(|task| task.get_labels() == &vec![ "Thesis".to_string() ]));
+
+        let in_progress = storage.query_tasks(todo_list.get_author(), Some(Status::InProgress), None)?;
+        assert_eq!(in_progress.len(), 1);
+
+        let pending = storage.query_tasks(todo_list.get_author(), Some(Status::Pending), None)?;
+        assert_eq!(pending.len(), todo_list.get_tasks().len() - 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn edit_and_remove_tasks() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        for (name, labels) in [
+            ("Practice the piano", vec![ "Music" ]),
+            ("Learn a new song", vec![ "Music", "Guitar" ]),
+            ("Write a paper", vec![ "Thesis" ]),
+        ] {
+            let task = Task::new(name, None, None, 2, labels, todo_list.get_author())?;
+            todo_list.add(task);
+        }
+        let paper_id = *todo_list.get_tasks()[2].get_id();
+
+        // Edit a task in place
+        todo_list.get_mut(&paper_id)?.change_priority(3);
+        assert_eq!(todo_list.get(&paper_id)?.get_priority(), &Priority::High);
+
+        // Complete the tasks in bulk
+        assert_eq!(todo_list.complete_with_label("Music"), 2);
+        assert_eq!(todo_list.complete_with_label("Music"), 0);
+        assert!(todo_list.get_tasks()[0].get_completed_on().is_some());
+
+        // Remove the tasks
+        let removed = todo_list.remove(&paper_id)?;
+        assert_eq!(removed.get_name(), "Write a paper");
+        assert!(matches!(todo_list.get(&paper_id), Err(RusticError::TaskNotFound(id)) if id == paper_id));
+        assert!(matches!(todo_list.remove(&paper_id), Err(RusticError::TaskNotFound(_))));
+
+        assert_eq!(todo_list.remove_with_label("Guitar").len(), 1);
+        todo_list.retain(|task| task.get_name() != "Practice the piano");
+        assert!(todo_list.get_tasks().is_empty());
+
+        // The bulk removals should take the subtasks and the links along
+        let tour = Task::new("Plan the tour", None, None, 2, vec![ "Music" ], &author)?;
+        let tour_id = *tour.get_id();
+        todo_list.add(tour);
+        let venue = Task::new("Book the venue", None, None, 2, vec![], &author)?;
+        let venue_id = *venue.get_id();
+        todo_list.add_subtask(&tour_id, venue)?;
+        let posters = Task::new("Print the posters", None, None, 2, vec![], &author)?;
+        let posters_id = *posters.get_id();
+        todo_list.add(posters);
+        todo_list.add_blocker(&posters_id, &venue_id)?;
+        let flyers = Task::new("Hand out the flyers", None, None, 2, vec![ "Music" ], &author)?;
+        let flyers_id = *flyers.get_id();
+        todo_list.add_subtask(&posters_id, flyers)?;
+
+        assert_eq!(todo_list.remove_with_label("Music").len(), 3);
+        assert!(matches!(todo_list.get(&venue_id), Err(RusticError::TaskNotFound(_))));
+        assert!(matches!(todo_list.get(&flyers_id), Err(RusticError::TaskNotFound(_))));
+        assert!(todo_list.get(&posters_id)?.get_blockers().is_empty());
+        assert!(todo_list.get(&posters_id)?.get_subtask_ids().is_empty());
+        assert!(!todo_list.is_blocked(&posters_id)?);
+
+        let snacks = Task::new("Buy the snacks", None, None, 2, vec![], &author)?;
+        let snacks_id = *snacks.get_id();
+        todo_list.add_subtask(&posters_id, snacks)?;
+        let banner = Task::new("Paint the banner", None, None, 2, vec![], &author)?;
+        let banner_id = *banner.get_id();
+        todo_list.add(banner);
+        todo_list.add_blocker(&banner_id, &snacks_id)?;
+        todo_list.retain(|task| task.get_id() != &posters_id);
+        assert_eq!(todo_list.get_tasks().len(), 1);
+        assert!(todo_list.get(&banner_id)?.get_blockers().is_empty());
+        assert_eq!(todo_list.get_root_tasks().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn start_and_end_session() -> Result<(), RusticError> {
+        let storage = MemoryStorage::new();
+        Author::build("John", "john@example.com", "password123")?.save(&storage)?;
+
+        let LoginState::LoggedIn(author) = Author::login(
+            "john@example.com",
+            "password123",
+            &storage,
+        )? else {
+            panic!("The login should be successful.");
+        };
+
+        let mut session = Session::start(author, &storage)?;
+        let task = Task::new("Practice the piano", None, None, 3, vec![], session.get_author())?;
+        session.get_todo_list_mut().add(task);
+        session.end(&storage)?;
+
+        let author = storage.find_author("john@example.com")?.expect("Author was saved");
+        let session = Session::start(author, &storage)?;
+        assert_eq!(session.get_todo_list().get_tasks().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn register_new_author() -> Result<(), RusticError> {
+        let storage = MemoryStorage::new();
+
+        let author = Author::register("John", "john@example.com", "password123", &storage)?;
+        assert_eq!(storage.find_author("john@example.com")?, Some(author));
+
+        assert!(matches!(
+            Author::register("John", "john@example.com", "password123", &storage),
+            Err(RusticError::AuthorExists),
+        ));
+        assert!(matches!(
+            Author::register("Jane", "jane@example@com", "password123", &storage),
+            Err(RusticError::InvalidEmail),
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeat_recurring_tasks() -> Result<(), RusticError> {
+        let weekly: Recurrence = "FREQ=WEEKLY;BYDAY=MO,WE".parse()?;
+        assert_eq!(weekly.to_string().parse::<Recurrence>()?, weekly);
+        assert!("FREQ=HOURLY".parse::<Recurrence>().is_err());
+
+        // The weekly rules without weekdays should round-trip as well
+        let every_week: Recurrence = "RRULE:FREQ=WEEKLY".parse()?;
+        assert_eq!(every_week, Recurrence::Weekly { interval: 1, weekdays: vec![] });
+        assert_eq!(every_week.to_string().parse::<Recurrence>()?, every_week);
+        assert_eq!("FREQ=WEEKLY;INTERVAL=1;BYDAY=".parse::<Recurrence>()?, every_week);
+
+        // Wednesday, then the next Monday
+        let due_date = parse_due_date("2024-06-10T16:00:00+05:30")?;
+        let next_due = weekly.next_due_date(Some(&due_date), &due_date).expect("There is a next date");
+        assert_eq!(next_due.date_naive().to_string(), "2024-06-12");
+        let next_due = weekly.next_due_date(Some(&next_due), &next_due).expect("There is a next date");
+        assert_eq!(next_due.date_naive().to_string(), "2024-06-17");
+
+        // Clamped to the end of February
+        let monthly: Recurrence = "FREQ=MONTHLY;BYMONTHDAY=31".parse()?;
+        let due_date = parse_due_date("2024-01-31T12:00:00+00:00")?;
+        let next_due = monthly.next_due_date(Some(&due_date), &due_date).expect("There is a next date");
+        assert_eq!(next_due.date_naive().to_string(), "2024-02-29");
+
+        // The huge intervals should be rejected, or end the repetition
+        assert!("FREQ=DAILY;INTERVAL=4000000000".parse::<Recurrence>().is_err());
+        assert!("FREQ=MONTHLY;INTERVAL=4000000000;BYMONTHDAY=1".parse::<Recurrence>().is_err());
+        assert!("FREQ=DAILY;INTERVAL=1000".parse::<Recurrence>().is_ok());
+        for recurrence in [
+            Recurrence::Daily { interval: u32::MAX },
+            Recurrence::Weekly { interval: u32::MAX, weekdays: vec![] },
+            Recurrence::Monthly { interval: u32::MAX, day: 1 },
+            Recurrence::AfterCompletion { days: u32::MAX },
+        ] {
+            assert_eq!(recurrence.next_due_date(Some(&due_date), &due_date), None);
+        }
+
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let mut task = Task::new("Water the plants", None, None, 2, vec![ "Home" ], todo_list.get_author())?;
+        task.set_recurrence(Some(Recurrence::AfterCompletion { days: 3 }));
+        let id = *task.get_id();
+        todo_list.add(task);
+
+        let next_id = todo_list.complete(&id)?.expect("The task is recurring");
+        let (done, next) = (todo_list.get(&id)?, todo_list.get(&next_id)?);
+        assert_eq!(done.get_status(), &Status::Complete);
+        assert_eq!(next.get_status(), &Status::Pending);
+        assert_eq!(next.get_labels(), done.get_labels());
+        assert!(next.get_due_date() > done.get_completed_on());
+
+        // Completing the task again should not add another occurrence
+        assert_eq!(todo_list.complete(&id)?, None);
+        todo_list.update_status(&id, Status::Complete)?;
+        assert!(todo_list.get_mut(&id)?.mark_completed().is_none());
+        assert_eq!(todo_list.get_tasks().len(), 2);
+
+        let mut task = Task::new("Renew the passport", None, None, 2, vec![], &author)?;
+        task.set_recurrence(Some(Recurrence::Monthly { interval: u32::MAX, day: 1 }));
+        let passport_id = *task.get_id();
+        todo_list.add(task);
+        assert_eq!(todo_list.complete(&passport_id)?, None);
+        assert_eq!(todo_list.get(&passport_id)?.get_status(), &Status::Complete);
+        todo_list.remove(&passport_id)?;
+
+        // The recurrence should survive the storage backends
+        let file_path = std::env::temp_dir().join("rustic_tasks_recurrence_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        todo_list.save_to_csv(&file_path)?;
+        let read_list = ToDoList::read_from_csv(todo_list.get_author().clone(), &file_path)?;
+        std::fs::remove_file(&file_path)?;
+        assert_eq!(read_list.get(&next_id)?.get_recurrence(), Some(&Recurrence::AfterCompletion { days: 3 }));
+
+        let storage = SqliteStorage::open_in_memory()?;
+        todo_list.save(&storage)?;
+        let loaded_tasks = storage.load_tasks(todo_list.get_author())?;
+        assert_eq!(loaded_tasks[1].get_recurrence(), Some(&Recurrence::AfterCompletion { days: 3 }));
+
+        let json = serde_json::to_string(todo_list.get(&next_id)?)?;
+        let task: Task = serde_json::from_str(&json)?;
+        assert_eq!(task.get_recurrence(), Some(&Recurrence::AfterCompletion { days: 3 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nest_subtasks() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let trip = Task::new("Plan a trip", None, None, 2, vec!["travel"], &author)?;
+        let trip_id = *trip.get_id();
+        todo_list.add(trip);
+
+        let tickets = Task::new("Book the tickets", None, None, 1, vec![], &author)?;
+        let tickets_id = *tickets.get_id();
+        todo_list.add_subtask(&trip_id, tickets)?;
+
+        let hotel = Task::new("Book the hotel", None, None, 1, vec![], &author)?;
+        let hotel_id = *hotel.get_id();
+        todo_list.add_subtask(&trip_id, hotel)?;
+
+        let seats = Task::new("Pick the seats", None, None, 3, vec![], &author)?;
+        let seats_id = *seats.get_id();
+        todo_list.add_subtask(&tickets_id, seats)?;
+
+        // The tree should be walked depth-first in the subtask order
+        let walked: Vec<(usize, Uuid)> = todo_list.walk()
+            .into_iter()
+            .map(|(depth, task)| (depth, *task.get_id()))
+            .collect();
+        assert_eq!(walked, vec![(0, trip_id), (1, tickets_id), (2, seats_id), (1, hotel_id)]);
+        assert_eq!(todo_list.get_root_tasks().len(), 1);
+        assert_eq!(todo_list.get(&seats_id)?.get_parent(), Some(&tickets_id));
+
+        // Completing every subtask should complete the parents
+        todo_list.complete(&seats_id)?;
+        assert_eq!(todo_list.get(&tickets_id)?.get_status(), &Status::Complete);
+        assert_eq!(todo_list.get_progress(&trip_id)?, (1, 2));
+        assert_eq!(todo_list.get(&trip_id)?.get_status(), &Status::Pending);
+        todo_list.complete(&hotel_id)?;
+        assert_eq!(todo_list.get(&trip_id)?.get_status(), &Status::Complete);
+
+        // Reopening a subtask should reopen its ancestors
+        todo_list.reopen(&seats_id)?;
+        assert_eq!(todo_list.get(&tickets_id)?.get_status(), &Status::Pending);
+        assert_eq!(todo_list.get(&trip_id)?.get_status(), &Status::Pending);
+
+        // The links should survive the storage backends
+        let file_path = std::env::temp_dir().join("rustic_tasks_subtasks_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        todo_list.save_to_csv(&file_path)?;
+        let read_list = ToDoList::read_from_csv(author.clone(), &file_path)?;
+        std::fs::remove_file(&file_path)?;
+        assert_eq!(read_list.get(&trip_id)?.get_subtask_ids(), &vec![tickets_id, hotel_id]);
+        assert_eq!(read_list.get(&seats_id)?.get_parent(), Some(&tickets_id));
+
+        let storage = SqliteStorage::open_in_memory()?;
+        todo_list.save(&storage)?;
+        let loaded_list = ToDoList::load(author, &storage)?;
+        assert_eq!(loaded_list.get(&trip_id)?.get_subtask_ids(), &vec![tickets_id, hotel_id]);
+        assert_eq!(loaded_list.get(&hotel_id)?.get_parent(), Some(&trip_id));
+
+        // Removing a task should remove its whole subtree
+        let removed = todo_list.remove(&tickets_id)?;
+        assert_eq!(removed.get_id(), &tickets_id);
+        assert!(todo_list.get(&seats_id).is_err());
+        assert_eq!(todo_list.get(&trip_id)?.get_subtask_ids(), &vec![hotel_id]);
+        assert_eq!(todo_list.get_tasks().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn block_tasks_with_dependencies() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let mut ids = Vec::new();
+        for (name, label) in [
+            ("Design the schema", "design"),
+            ("Write the migrations", "project"),
+            ("Ship the release", "project"),
+        ] {
+            let task = Task::new(name, None, None, 2, vec![label], &author)?;
+            ids.push(*task.get_id());
+            todo_list.add(task);
+        }
+        let (design, migrations, release) = (ids[0], ids[1], ids[2]);
+
+        todo_list.add_blocker(&migrations, &design)?;
+        todo_list.add_blocker(&release, &migrations)?;
+
+        // Closing the loop should be rejected
+        assert!(matches!(
+            todo_list.add_blocker(&design, &release),
+            Err(RusticError::DependencyCycle(..))
+        ));
+        assert!(matches!(
+            todo_list.add_blocker(&design, &design),
+            Err(RusticError::DependencyCycle(..))
+        ));
+
+        let actionable: Vec<&Uuid> = todo_list.get_actionable_tasks()
+            .into_iter()
+            .map(Task::get_id)
+            .collect();
+        assert_eq!(actionable, vec![&design]);
+        assert_eq!(todo_list.get_blocked_tasks().len(), 2);
+
+        // Blocked tasks can neither be started nor completed
+        assert!(matches!(
+            todo_list.update_status(&migrations, Status::InProgress),
+            Err(RusticError::TaskBlocked(_))
+        ));
+        assert!(matches!(todo_list.complete(&release), Err(RusticError::TaskBlocked(_))));
+        assert_eq!(todo_list.complete_with_label("project"), 0);
+        assert_eq!(todo_list.complete_with_label("design"), 1);
+
+        todo_list.update_status(&migrations, Status::InProgress)?;
+        assert_eq!(todo_list.get(&migrations)?.get_status(), &Status::InProgress);
+        assert!(todo_list.is_blocked(&release)?);
+
+        // The blockers should survive the storage backends
+        let file_path = std::env::temp_dir().join("rustic_tasks_blockers_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        todo_list.save_to_csv(&file_path)?;
+        let read_list = ToDoList::read_from_csv(author.clone(), &file_path)?;
+        std::fs::remove_file(&file_path)?;
+        assert_eq!(read_list.get(&release)?.get_blockers(), &vec![migrations]);
+
+        let storage = SqliteStorage::open_in_memory()?;
+        todo_list.save(&storage)?;
+        let loaded_list = ToDoList::load(author, &storage)?;
+        assert_eq!(loaded_list.get(&migrations)?.get_blockers(), &vec![design]);
+
+        // Removing a blocker should unblock the waiting task
+        todo_list.remove(&migrations)?;
+        assert!(todo_list.get(&release)?.get_blockers().is_empty());
+        assert!(!todo_list.is_blocked(&release)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn record_task_history() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let start = Local::now();
+
+        let mut task = Task::new("Draft the agenda", None, None, 2, vec!["work"], &author)?;
+        task.rename("Draft the meeting agenda");
+        task.change_priority(3);
+        task.change_priority(3);
+        task.update_labels(vec!["work", "meeting"]);
+        let id = *task.get_id();
+        todo_list.add(task);
+
+        let blocker = Task::new("Book the room", None, None, 2, vec![], &author)?;
+        let blocker_id = *blocker.get_id();
+        todo_list.add(blocker);
+        todo_list.add_blocker(&id, &blocker_id)?;
+        todo_list.complete(&blocker_id)?;
+        todo_list.complete(&id)?;
+        todo_list.reopen(&id)?;
+
+        // Every change should be kept in order, skipping the ones that changed nothing
+        let history = todo_list.get(&id)?.get_history();
+        let fields: Vec<&TaskField> = history.iter().map(TaskEvent::get_field).collect();
+        assert_eq!(fields, vec![
+            &TaskField::Name,
+            &TaskField::Priority,
+            &TaskField::Labels,
+            &TaskField::Blockers,
+            &TaskField::Status,
+            &TaskField::CompletedOn,
+            &TaskField::Status,
+            &TaskField::CompletedOn,
+        ]);
+        assert_eq!(history[0].get_changed_by(), author.get_id().to_string());
+        assert_eq!(history[0].get_old_value(), Some("Draft the agenda"));
+        assert_eq!(history[0].get_new_value(), Some("Draft the meeting agenda"));
+        assert_eq!(history[1].get_old_value(), Some("Medium"));
+        assert_eq!(history[1].get_new_value(), Some("High"));
+        assert_eq!(history[2].get_new_value(), Some("work,meeting"));
+        assert_eq!(history[3].get_old_value(), None);
+        assert_eq!(history[6].get_new_value(), Some("Pending"));
+        assert_eq!(history[7].get_new_value(), None);
+
+        // The changes saved by the name of the author should be upgraded
+        let author_id = author.get_id().to_string();
+        let json = serde_json::to_string(todo_list.get(&id)?)?.replace(&author_id, "John");
+        let mut legacy_tasks: Vec<Task> = vec![serde_json::from_str(&json)?];
+        assert!(upgrade_task_owners(&mut legacy_tasks, std::slice::from_ref(&author)));
+        assert!(legacy_tasks[0].get_history().iter().all(|event| event.get_changed_by() == author_id));
+
+        // The changes of a period should cover every task
+        let end = Local::now() + Duration::seconds(1);
+        assert_eq!(todo_list.get(&id)?.get_changes_between(&start, &end).len(), 8);
+        assert!(todo_list.get(&id)?.get_changes_between(&end, &(end + Duration::days(1))).is_empty());
+        let changes = todo_list.get_changes_between(&start, &end);
+        assert_eq!(changes.len(), 10);
+        assert!(changes.windows(2).all(|pair| pair[0].1.get_changed_on() <= pair[1].1.get_changed_on()));
+
+        // The history should survive the storage backends
+        let file_path = std::env::temp_dir().join("rustic_tasks_history_test.csv");
+        let _ = std::fs::remove_file(&file_path);
+        todo_list.save_to_csv(&file_path)?;
+        let read_list = ToDoList::read_from_csv(author.clone(), &file_path)?;
+        std::fs::remove_file(&file_path)?;
+        assert_eq!(read_list.get(&id)?.get_history(), history);
+
+        let storage = SqliteStorage::open_in_memory()?;
+        todo_list.save(&storage)?;
+        let loaded_list = ToDoList::load(author.clone(), &storage)?;
+        assert_eq!(loaded_list.get(&id)?.get_history(), history);
+        let events = storage.query_events(&author, &start, &end)?;
+        assert_eq!(events.len(), 10);
+        assert_eq!(events.iter().filter(|(task_id, _)| task_id == &blocker_id).count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn undo_and_redo_task_commands() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let names = |todo_list: &ToDoList| -> Vec<String> {
+            todo_list.get_tasks().iter().map(|task| task.get_name().to_string()).collect()
+        };
+
+        let mut ids = Vec::new();
+        for name in ["Plan the trip", "Pack the bags", "Water the plants"] {
+            let task = Task::new(name, None, None, 2, vec![], &author)?;
+            ids.push(*task.get_id());
+            todo_list.execute(TaskCommand::Add { task, parent: None })?;
+        }
+        let (trip, bags, plants) = (ids[0], ids[1], ids[2]);
+
+        let mut tickets = Task::new("Book the tickets", None, Some("2024-06-10"), 2, vec![], &author)?;
+        tickets.set_recurrence(Some(Recurrence::Daily { interval: 1 }));
+        let tickets_id = *tickets.get_id();
+        todo_list.execute(TaskCommand::Add { task: tickets, parent: Some(trip) })?;
+        assert_eq!(todo_list.get(&trip)?.get_subtask_ids(), &vec![tickets_id]);
+
+        // Undoing should take back the side effects of the command as well
+        todo_list.execute(TaskCommand::ToggleComplete(tickets_id))?;
+        assert_eq!(todo_list.get_tasks().len(), 5);
+        assert_eq!(todo_list.get(&trip)?.get_subtask_ids().len(), 2);
+        assert!(matches!(todo_list.undo(), Some(TaskCommand::ToggleComplete(_))));
+        assert_eq!(todo_list.get_tasks().len(), 4);
+        assert_eq!(todo_list.get(&tickets_id)?.get_status(), &Status::Pending);
+        // The undo should be recorded in the history like any other change
+        assert_eq!(todo_list.get(&tickets_id)?.get_history().len(), 5);
+
+        // Redoing should restore the exact same tasks
+        todo_list.redo();
+        let next_id = todo_list.get(&trip)?.get_subtask_ids()[1];
+        todo_list.undo();
+        todo_list.redo();
+        assert_eq!(todo_list.get(&trip)?.get_subtask_ids()[1], next_id);
+        assert!(todo_list.redo().is_none());
+
+        let edit = TaskEdit {
+            name: Some("Water the garden".to_string()),
+            priority: Some(Priority::High),
+            labels: Some(vec!["home".to_string()]),
+            ..TaskEdit::default()
+        };
+        todo_list.execute(TaskCommand::Edit(plants, edit))?;
+        assert_eq!(todo_list.get(&plants)?.get_name(), "Water the garden");
+        assert_eq!(todo_list.get(&plants)?.get_priority(), &Priority::High);
+        assert_eq!(todo_list.get(&plants)?.get_history().len(), 3);
+
+        todo_list.execute(TaskCommand::Reorder { id: plants, index: 0 })?;
+        assert_eq!(names(&todo_list)[0], "Water the garden");
+        todo_list.execute(TaskCommand::Reorder { id: next_id, index: 0 })?;
+        assert_eq!(todo_list.get(&trip)?.get_subtask_ids(), &vec![next_id, tickets_id]);
+
+        todo_list.execute(TaskCommand::Remove(trip))?;
+        assert_eq!(names(&todo_list), vec!["Water the garden", "Pack the bags"]);
+
+        // Every command should be undone in the reverse order
+        todo_list.undo();
+        assert_eq!(todo_list.get_tasks().len(), 5);
+        todo_list.undo();
+        assert_eq!(todo_list.get(&trip)?.get_subtask_ids(), &vec![tickets_id, next_id]);
+        todo_list.undo();
+        assert_eq!(names(&todo_list)[0], "Plan the trip");
+        todo_list.undo();
+        assert_eq!(todo_list.get(&plants)?.get_name(), "Water the plants");
+        assert_eq!(todo_list.get(&plants)?.get_history().len(), 6);
+
+        // A new command should forget the undone ones
+        todo_list.execute(TaskCommand::Remove(bags))?;
+        assert!(todo_list.get_undo_stack().next_redo().is_none());
+        assert!(matches!(todo_list.get_undo_stack().next_undo(), Some(TaskCommand::Remove(_))));
+
+        // The failed commands should neither change the list nor be undone
+        todo_list.add_blocker(&plants, &trip)?;
+        assert!(matches!(
+            todo_list.execute(TaskCommand::ToggleComplete(plants)),
+            Err(RusticError::TaskBlocked(_))
+        ));
+        assert_eq!(todo_list.get(&plants)?.get_status(), &Status::Pending);
+        assert!(matches!(todo_list.get_undo_stack().next_undo(), Some(TaskCommand::Remove(_))));
+
+        // Undoing should leave the changes made outside the commands alone
+        todo_list.undo();
+        assert_eq!(todo_list.get(&bags)?.get_name(), "Pack the bags");
+        assert_eq!(todo_list.get(&plants)?.get_blockers(), &vec![trip]);
+
+        while todo_list.undo().is_some() {}
+        assert!(todo_list.get_tasks().is_empty());
+        for _ in 0..3 {
+            todo_list.redo();
+        }
+        assert_eq!(names(&todo_list), vec!["Plan the trip", "Pack the bags", "Water the plants"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replay_tasks_from_journal() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let other = Author::build("Jane", "jane@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+        let mut journal = Journal::default();
+
+        let mut ids = Vec::new();
+        for name in ["Plan the trip", "Pack the bags", "Water the plants"] {
+            let task = Task::new(name, None, None, 2, vec![], &author)?;
+            ids.push(*task.get_id());
+            todo_list.add(task);
+        }
+        let (trip, bags, plants) = (ids[0], ids[1], ids[2]);
+        let entries = journal.record(&author, todo_list.get_tasks());
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| matches!(entry.get_event(), JournalEvent::TaskCreated { .. })));
+        let created = Local::now();
+
+        // Saving the same tasks again should not record anything
+        assert!(journal.record(&author, todo_list.get_tasks()).is_empty());
+
+        todo_list.complete(&bags)?;
+        todo_list.get_mut(&plants)?.rename("Water the garden");
+        todo_list.remove_tree(&trip)?;
+        todo_list.move_task(&plants, 0)?;
+        let entries = journal.record(&author, todo_list.get_tasks());
+        let events: Vec<&JournalEvent> = entries.iter().map(JournalEntry::get_event).collect();
+        assert!(matches!(events[..], [
+            JournalEvent::TaskUpdated { .. },
+            JournalEvent::TaskCompleted { .. },
+            JournalEvent::TaskDeleted { .. },
+            JournalEvent::TasksReordered { .. },
+        ]));
+        let sequences: Vec<u64> = journal.get_entries().iter().map(JournalEntry::get_sequence).collect();
+        assert_eq!(sequences, (1..=7).collect::<Vec<u64>>());
+
+        // The journal should rebuild the list now and at any point in time
+        let names = |tasks: &[Task]| -> Vec<String> {
+            tasks.iter().map(|task| task.get_name().to_string()).collect()
+        };
+        let replayed = ToDoList::replay(author.clone(), &journal, None);
+        assert_eq!(names(replayed.get_tasks()), vec!["Water the garden", "Pack the bags"]);
+        assert_eq!(replayed.get(&bags)?.get_status(), &Status::Complete);
+        assert_eq!(replayed.get(&plants)?.get_history(), todo_list.get(&plants)?.get_history());
+        let replayed = ToDoList::replay(author.clone(), &journal, Some(&created));
+        assert_eq!(names(replayed.get_tasks()), vec!["Plan the trip", "Pack the bags", "Water the plants"]);
+        assert!(journal.replay(other.get_id(), None).is_empty());
+
+        // The replays should start from the latest snapshot
+        let snapshot = journal.snapshot(&author);
+        assert_eq!(snapshot.get_sequence(), 8);
+        assert_eq!(names(&journal.replay(author.get_id(), None)), vec!["Water the garden", "Pack the bags"]);
+
+        // Compaction should only keep the latest state before then
+        let task = Task::new("Call the bank", None, None, 3, vec![], &other)?;
+        journal.record(&other, &[task]);
+        let compacted = Local::now();
+        todo_list.add(Task::new("Book the hotel", None, None, 1, vec![], &author)?);
+        journal.record(&author, todo_list.get_tasks());
+        assert_eq!(journal.compact(&compacted), 7);
+        assert_eq!(journal.get_entries().len(), 3);
+        assert_eq!(names(&journal.replay(author.get_id(), None)), vec!["Water the garden", "Pack the bags", "Book the hotel"]);
+        assert_eq!(names(&journal.replay(other.get_id(), None)), vec!["Call the bank"]);
+        assert_eq!(names(&journal.replay(author.get_id(), Some(&created))), Vec::<String>::new());
+
+        // The storage should only append the changes to the journal file
+        let authors_path = std::env::temp_dir().join("rustic_tasks_journal_test.csv");
+        let journal_path = std::env::temp_dir().join("rustic_tasks_journal_test.jsonl");
+        let _ = std::fs::remove_file(&authors_path);
+        let _ = std::fs::remove_file(&journal_path);
+        let storage = JournalStorage::new(&authors_path, &journal_path);
+        storage.save_author(&author)?;
+        assert_eq!(storage.load_authors()?.len(), 1);
+        todo_list.save(&storage)?;
+        todo_list.save(&storage)?;
+        assert_eq!(storage.read_journal()?.get_entries().len(), 3);
+        let saved = Local::now();
+        todo_list.complete(&plants)?;
+        todo_list.save(&storage)?;
+        let loaded_list = ToDoList::load(author.clone(), &storage)?;
+        assert_eq!(loaded_list.get(&plants)?.get_status(), &Status::Complete);
+        assert_eq!(storage.load_tasks_at(&author, &saved)?.len(), 3);
+        assert_eq!(storage.load_tasks_at(&author, &saved)?[0].get_status(), &Status::Pending);
+        assert_eq!(storage.compact(&Local::now())?, 3);
+        assert_eq!(ToDoList::load(author.clone(), &storage)?.get_tasks().len(), 3);
+        std::fs::remove_file(&authors_path)?;
+        std::fs::remove_file(&journal_path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_tasks_with_filters() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        todo_list.add(Task::new(
+            "Prepare the presentation",
+            Some("Slides for the quarterly review"),
+            Some("2024-06-20T17:00:00+0000"),
+            3,
+            vec!["Work"],
+            &author,
+        )?);
+        todo_list.add(Task::new(
+            "Review the presentation",
+            None,
+            Some("2024-07-05T09:00:00+0000"),
+            2,
+            vec!["work", "review"],
+            &author,
+        )?);
+        todo_list.add(Task::new("Buy groceries", None, None, 1, vec!["home"], &author)?);
+
+        let names = |tasks: Vec<&Task>| -> Vec<String> {
+            tasks.iter().map(|task| task.get_name().to_string()).collect()
+        };
+
+        let tasks = todo_list.query(
+            "status:pending priority:>=medium label:Work due:<2024-07-01 \"presentation\""
+        )?;
+        assert_eq!(names(tasks), vec!["Prepare the presentation"]);
+
+        assert_eq!(todo_list.query("label:work")?.len(), 2);
+        assert_eq!(todo_list.query("-label:work")?.len(), 1);
+        assert_eq!(todo_list.query("priority:low")?.len(), 1);
+        assert_eq!(todo_list.query("quarterly")?.len(), 1);
+        assert_eq!(todo_list.query("\"buy groceries\"")?.len(), 1);
+        assert_eq!(todo_list.query("due:>=2024-06-20")?.len(), 2);
+        assert_eq!(todo_list.query("")?.len(), 3);
+        assert_eq!(todo_list.query("status:done")?.len(), 0);
+
+        let query: Query = "priority:>high label:\"Deep Work\"".parse()?;
+        assert_eq!(query.get_filters(), &vec![
+            Filter::Priority(Comparison::Greater, Priority::High),
+            Filter::Label("Deep Work".to_string()),
+        ]);
+
+        // The errors should point at the invalid part of the query
+        for (query, expected_position) in [
+            ("status:pending priority:urgent", 24),
+            ("label:work owner:john", 11),
+            ("due:<07/01/2024", 4),
+            ("\"unfinished quote", 0),
+            ("status:", 7),
+        ] {
+            match todo_list.query(query) {
+                Err(RusticError::InvalidQuery(position, _)) => {
+                    assert_eq!(position, expected_position, "{query}");
+                },
+                result => panic!("Expected an invalid query for {query}, got {result:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_and_group_tasks() -> Result<(), RusticError> {
+        let author = Author::build("John", "john@example.com", "password123")?;
+        let mut todo_list = ToDoList::build(author.clone())?;
+
+        let now = Local::now();
+        let in_days = |days: i64| (now + Duration::days(days)).format("%Y-%m-%dT%H:%M:%S%z").to_string();
+
+        let mut ids = Vec::new();
+        for (name, due_date, priority, labels) in [
+            ("water the plants", Some(in_days(-1)), 1, vec!["home"]),
+            ("Answer the emails", Some(in_days(30)), 3, vec!["work"]),
+            ("Book the flights", None, 2, vec!["travel", "work"]),
+            ("Call the bank", None, 2, vec![]),
+        ] {
+            let task = Task::new(name, None, due_date.as_deref(), priority, labels, &author)?;
+            ids.push(*task.get_id());
+            todo_list.add(task);
+        }
+        todo_list.complete(&ids[1])?;
+
+        let names = |tasks: Vec<&Task>| -> Vec<String> {
+            tasks.iter().map(|task| task.get_name().to_string()).collect()
+        };
+
+        assert_eq!(
+            names(todo_list.sorted(SortKey::Name, SortOrder::Ascending)),
+            vec!["Answer the emails", "Book the flights", "Call the bank", "water the plants"],
+        );
+        assert_eq!(
+            names(todo_list.sorted(SortKey::DueDate, SortOrder::Descending))[..2],
+            ["Answer the emails", "water the plants"],
+        );
+        assert_eq!(
+            names(todo_list.sorted(SortKey::Priority, SortOrder::Descending))[0],
+            "Answer the emails",
+        );
+
+        // The ties should be broken by the id in both orders
+        let tied: Vec<Uuid> = todo_list.sorted(SortKey::Priority, SortOrder::Ascending)
+            .into_iter()
+            .filter(|task| task.get_priority() == &Priority::Medium)
+            .map(|task| *task.get_id())
+            .collect();
+        assert!(tied[0] < tied[1]);
+        let tied_descending: Vec<Uuid> = todo_list.sorted(SortKey::Priority, SortOrder::Descending)
+            .into_iter()
+            .filter(|task| task.get_priority() == &Priority::Medium)
+            .map(|task| *task.get_id())
+            .collect();
+        assert_eq!(tied, tied_descending);
+
+        let groups = todo_list.grouped(GroupBy::Status, SortKey::Name, SortOrder::Ascending, &now);
+        assert_eq!(groups[0].0, GroupKey::Status(Status::Pending));
+        assert_eq!(groups[1].0, GroupKey::Status(Status::Complete));
+        assert_eq!(groups[0].1.len(), 3);
+
+        let groups = todo_list.grouped(GroupBy::Label, SortKey::Name, SortOrder::Ascending, &now);
+        let group_names: Vec<String> = groups.iter().map(|(key, _)| key.to_string()).collect();
+        assert_eq!(group_names, vec!["home", "travel", "work", "No Label"]);
+        assert_eq!(names(groups[2].1.clone()), vec!["Answer the emails", "Book the flights"]);
+
+        let groups = todo_list.grouped(GroupBy::DueBucket, SortKey::Name, SortOrder::Ascending, &now);
+        let group_keys: Vec<GroupKey> = groups.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(group_keys, vec![
+            GroupKey::Due(DueBucket::Overdue),
+            GroupKey::Due(DueBucket::Later),
+            GroupKey::Due(DueBucket::NoDate),
+        ]);
+
+        // The due buckets should follow the calendar
+        let today = now.date_naive().and_hms_opt(23, 59, 59).unwrap().and_local_timezone(Local).unwrap();
+        assert_eq!(DueBucket::of(Some(&today), &now), DueBucket::Today);
+        assert_eq!(DueBucket::of(None, &now), DueBucket::NoDate);
+        assert!(Priority::High > Priority::Low && Status::Complete > Status::InProgress);
+        assert_eq!(Status::InProgress.to_string(), "In Progress");
+        assert_eq!("due-date".parse::<SortKey>()?, SortKey::DueDate);
+        assert!("colour".parse::<GroupBy>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
     fn build_gtk_app() {
         let exit_code = App::build();
         println!("{:?}", exit_code);